@@ -1,23 +1,34 @@
-use crate::network::{PlayerMapping, ProjectileMapping};
+use crate::interpolation::{EntitySnapshot, SnapshotBuffer};
+use crate::network::clock::ServerClock;
+use crate::network::{FlagMapping, MonsterMapping, PelletMapping, PickupMapping, PlayerMapping, ProjectileMapping, ReceivedSnapshots};
+use crate::prediction::PendingReconciliation;
 use bevy::asset::Assets;
-use bevy::image::{TextureAtlas, TextureAtlasLayout};
+use bevy::image::TextureAtlasLayout;
 use bevy::math::Vec3;
-use bevy::prelude::{Children, Commands, Entity, Quat, Query, Res, ResMut, Sprite, Transform, With};
-use bevy::utils::default;
-use bevy_renet2::prelude::RenetClient;
-use game_core::network::network::{NetworkedEntities, ServerChannel};
-use game_core::player::command::handle_from_player_texture;
-use game_core::player::component::PlayerNetwork;
+use bevy::prelude::{Changed, Children, Circle, DetectChangesMut, Color, ColorMaterial, Commands, Component, Entity, Mesh, Mesh2d, MeshMaterial2d, Or, Query, Res, ResMut, Sprite, Time, Timer, TimerMode, Transform, With};
+use bevy::color::Alpha;
+use game_core::game_mode::component::Team;
+use game_core::game_mode::flag::flag_sprite;
+use game_core::monster::command::monster_sprite;
+use game_core::monster::texture::MonsterTextures;
+use game_core::network::network::{NetworkedEntities, NetworkedPlayer};
+use game_core::pickup::command::pickup_sprite;
+use game_core::player::command::player_sprite;
+use game_core::player::component::{ControlledPlayer, PlayerChildren, PlayerInput, PlayerNetwork, PlayerWeaponSelected};
 use game_core::player::texture::PlayerTextures;
+use game_core::texture::component::SpriteFrame;
+use game_core::texture::math::is_face_right;
 use game_core::weapon::animation::{weapon_rotation, weapon_sprite_flip};
-use game_core::weapon::command::{handle_from_weapon_fx_texture, spawn_weapon_fx};
-use game_core::weapon::component::{PivotDisk, Weapon};
-use game_core::weapon::fx_texture::{FxComponent, WeaponFxTextures};
+use game_core::weapon::command::{spawn_weapon_fx, weapon_fx_sprite, weapon_sprite};
+use game_core::weapon::component::{PivotDisk, ProjectileOwner, Weapon};
+use game_core::weapon::fx_texture::{WeaponFxTextureEntity, WeaponFxTextures};
+use game_core::weapon::math::{projectile_directions, projectile_position};
+use game_core::weapon::texture::{WeaponTextureEntity, WeaponTextures};
 
 #[allow(clippy::too_many_arguments)]
 pub fn player_animation(
     mut commands: Commands,
-    mut client: ResMut<RenetClient>,
+    mut received_snapshots: ResMut<ReceivedSnapshots>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut disk_query: Query<(&mut Transform, &Children), With<PivotDisk>>,
     mut weapon_query: Query<&mut Sprite, With<Weapon>>,
@@ -25,28 +36,38 @@ pub fn player_animation(
     mut projectile_mapping: ResMut<ProjectileMapping>,
     player_textures: Res<PlayerTextures>,
     mut weapon_fx_textures: Res<WeaponFxTextures>,
-    player_query: Query<&Children, With<PlayerNetwork>>,
-    weapon_fx_query: Query<&FxComponent>,
+    mut player_query: Query<(&Children, &mut PlayerWeaponSelected), With<PlayerNetwork>>,
+    controlled_query: Query<(), With<ControlledPlayer>>,
+    mut reconciliation: ResMut<PendingReconciliation>,
+    mut snapshot_query: Query<&mut SnapshotBuffer>,
+    mut pellet_mapping: ResMut<PelletMapping>,
+    server_clock: Res<ServerClock>,
 ) {
-    while let Some(message) = client.receive_message(ServerChannel::NetworkedEntities) {
-        let networked_entities: NetworkedEntities = bincode::deserialize(&message).unwrap();
-
-        for player_entity_index in 0..networked_entities.entities.len() {
-            if !networked_entities.entities.is_empty() {
-                if let Some(entity) = player_mapping.0.get(&Entity::from_bits(networked_entities.entities[player_entity_index])) {
-                    animate_player(
-                        entity,
-                        &networked_entities,
-                        player_entity_index,
-                        &mut commands,
-                        &mut texture_atlas_layouts,
-                        &player_textures,
-                    );
+    for networked_entities in received_snapshots.0.drain(..) {
+        for player in networked_entities.players() {
+            if let Some(entity) = player_mapping.0.get(&Entity::from_bits(player.entity)) {
+                if controlled_query.contains(*entity) {
+                    reconciliation.0 = Some(player.clone());
+                } else if let Ok(mut snapshot_buffer) = snapshot_query.get_mut(*entity) {
+                    snapshot_buffer.push(EntitySnapshot {
+                        tick: networked_entities.tick,
+                        translation: player.translation.into(),
+                        rotation: 0.0,
+                    });
+                }
+
+                animate_player(
+                    entity,
+                    player,
+                    &mut commands,
+                    &mut texture_atlas_layouts,
+                    &player_textures,
+                );
+                if let Ok((children, mut player_weapon_selected)) = player_query.get_mut(*entity) {
+                    player_weapon_selected.set_if_neq(player.weapon_texture_entity_type.clone());
                     animate_weapon(
-                        entity,
-                        &networked_entities,
-                        player_entity_index,
-                        player_query,
+                        player,
+                        children,
                         &mut disk_query,
                         &mut weapon_query,
                     );
@@ -54,77 +75,211 @@ pub fn player_animation(
             }
         }
 
-        for _projectile_entity_index in 0..networked_entities.projectile_entities.len() {
-            if !networked_entities.projectile_entities.is_empty() {
-                animate_weapon_fx(
-                    &networked_entities,
-                    &mut projectile_mapping,
-                    &mut commands,
-                    &mut texture_atlas_layouts,
-                    &mut weapon_fx_textures,
-                    weapon_fx_query,
-                );
-            }
-        }
+        animate_weapon_fx(
+            &networked_entities,
+            &mut projectile_mapping,
+            &mut commands,
+            &mut texture_atlas_layouts,
+            &mut weapon_fx_textures,
+            &mut snapshot_query,
+        );
+        animate_pellet_volleys(
+            &networked_entities,
+            &mut pellet_mapping,
+            &mut commands,
+            &mut texture_atlas_layouts,
+            &mut weapon_fx_textures,
+            &mut snapshot_query,
+            server_clock.tick_rate(),
+        );
     }
 }
 fn animate_player(
     entity: &Entity,
-    networked_entities: &NetworkedEntities,
-    entity_index: usize,
+    player: &NetworkedPlayer,
     commands: &mut Commands,
     texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
     player_textures: &Res<PlayerTextures>,
 ) {
-    let (image, layout) = handle_from_player_texture(
-        &networked_entities.player_texture_entity_type[entity_index],
-        texture_atlas_layouts,
-        player_textures,
-    );
-
-    let translation = networked_entities.translations[entity_index].into();
+    let sprite_frame = SpriteFrame {
+        index: player.sprite_index,
+        flip_x: player.sprite_flip_x,
+        ..Default::default()
+    };
 
     commands.entity(*entity)
-        .insert(Sprite {
-            image,
-            texture_atlas: Some(TextureAtlas {
-                layout,
-                index: networked_entities.sprite_index[entity_index],
-            }),
-            flip_x: networked_entities.sprite_flip_x[entity_index],
-            ..Default::default()
-        })
-        .insert(Transform {
-            translation,
-            scale: Vec3::splat(0.5),
-            ..Default::default()
-        });
+        .insert(player_sprite(
+            &player.player_texture_entity_type,
+            sprite_frame,
+            texture_atlas_layouts,
+            player_textures,
+        ));
 }
 
 
 fn animate_weapon(
-    entity: &Entity,
-    networked_entities: &NetworkedEntities,
-    entity_index: usize,
-    player_query: Query<&Children, With<PlayerNetwork>>,
+    player: &NetworkedPlayer,
+    children: &Children,
     disk_query: &mut Query<(&mut Transform, &Children), With<PivotDisk>>,
     weapon_query: &mut Query<&mut Sprite, With<Weapon>>,
 ) {
-    if let Ok(children) = player_query.get(*entity) {
-        for &child in children.iter() {
-            if let Ok((mut transform, weapon_children)) = disk_query.get_mut(child) {
-                let aim_direction = networked_entities.player_aim_direction[entity_index];
-                weapon_rotation(&mut transform, aim_direction);
-                for &weapon_entity in weapon_children.iter() {
-                    if let Ok(mut weapon_sprite) = weapon_query.get_mut(weapon_entity) {
-                        weapon_sprite_flip(&mut weapon_sprite, aim_direction);
-                    }
+    for &child in children.iter() {
+        if let Ok((mut transform, weapon_children)) = disk_query.get_mut(child) {
+            let aim_direction = player.aim_direction;
+            weapon_rotation(&mut transform, aim_direction);
+            for &weapon_entity in weapon_children.iter() {
+                if let Ok(mut weapon_sprite) = weapon_query.get_mut(weapon_entity) {
+                    weapon_sprite_flip(&mut weapon_sprite, aim_direction);
                 }
             }
         }
     }
 }
 
+/// Swaps the child `Weapon` sprite when the server reports another drawn weapon.
+pub fn update_weapon_sprites(
+    mut commands: Commands,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    weapon_textures: Res<WeaponTextures>,
+    player_query: Query<(&PlayerWeaponSelected, &PlayerChildren, &PlayerInput), Changed<PlayerWeaponSelected>>,
+) {
+    for (player_weapon_selected, children, player_input) in player_query.iter() {
+        let weapon_texture_type = &player_weapon_selected.weapon_texture_type;
+        let mut sprite = weapon_sprite(
+            weapon_texture_type,
+            SpriteFrame {
+                index: WeaponTextureEntity::new(weapon_texture_type).animation_indices.first as usize,
+                ..Default::default()
+            },
+            &mut texture_atlas_layouts,
+            &weapon_textures,
+        );
+        weapon_sprite_flip(&mut sprite, player_input.aim_direction);
+        commands.entity(children.weapon).insert(sprite);
+    }
+}
+
+/// Mirrors the pickups of the latest snapshot; collected ones are absent until they respawn.
+pub fn pickup_animation(
+    mut commands: Commands,
+    received_snapshots: Res<ReceivedSnapshots>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    weapon_textures: Res<WeaponTextures>,
+    mut pickup_mapping: ResMut<PickupMapping>,
+) {
+    let Some(networked_entities) = received_snapshots.0.last() else { return };
+
+    let mut active_pickups = std::collections::HashSet::new();
+    for pickup in networked_entities.pickups() {
+        let entity = Entity::from_bits(pickup.entity);
+        active_pickups.insert(entity);
+        if pickup_mapping.0.contains_key(&entity) {
+            continue;
+        }
+
+        let client_entity = commands.spawn((
+            pickup.pickup.clone(),
+            pickup_sprite(&pickup.pickup, &mut texture_atlas_layouts, &weapon_textures),
+            Transform::from_translation(pickup.translation.into()),
+        )).id();
+        pickup_mapping.0.insert(entity, client_entity);
+    }
+
+    pickup_mapping.0.retain(|entity, client_entity| {
+        let active = active_pickups.contains(entity);
+        if !active {
+            commands.entity(*client_entity).despawn();
+        }
+        active
+    });
+}
+
+/// Mirrors the flags of the latest snapshot; carried flags simply follow the snapshots of their carrier.
+pub fn flag_animation(
+    mut commands: Commands,
+    received_snapshots: Res<ReceivedSnapshots>,
+    mut flag_mapping: ResMut<FlagMapping>,
+    mut transform_query: Query<&mut Transform>,
+) {
+    let Some(networked_entities) = received_snapshots.0.last() else { return };
+
+    for flag in networked_entities.flags() {
+        let entity = Entity::from_bits(flag.entity);
+        match flag_mapping.0.get(&entity) {
+            Some(client_entity) => {
+                if let Ok(mut transform) = transform_query.get_mut(*client_entity) {
+                    transform.translation = flag.translation.into();
+                }
+            }
+            None => {
+                let client_entity = commands.spawn((
+                    flag_sprite(flag.team),
+                    Transform::from_translation(flag.translation.into()),
+                )).id();
+                flag_mapping.0.insert(entity, client_entity);
+            }
+        }
+    }
+}
+
+/// Monsters are interpolated like remote players and disappear with the first snapshot that omits them.
+pub fn monster_animation(
+    mut commands: Commands,
+    received_snapshots: Res<ReceivedSnapshots>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    monster_textures: Res<MonsterTextures>,
+    mut monster_mapping: ResMut<MonsterMapping>,
+    mut snapshot_query: Query<&mut SnapshotBuffer>,
+) {
+    for networked_entities in received_snapshots.0.iter() {
+        let mut active_monsters = std::collections::HashSet::new();
+        for monster in networked_entities.monsters() {
+            let entity = Entity::from_bits(monster.entity);
+            active_monsters.insert(entity);
+
+            let snapshot = EntitySnapshot {
+                tick: networked_entities.tick,
+                translation: monster.translation.into(),
+                rotation: 0.0,
+            };
+            let sprite = monster_sprite(
+                &monster.monster_type,
+                SpriteFrame {
+                    index: monster.sprite_index,
+                    flip_x: monster.sprite_flip_x,
+                    ..Default::default()
+                },
+                &mut texture_atlas_layouts,
+                &monster_textures,
+            );
+
+            if let Some(client_entity) = monster_mapping.0.get(&entity) {
+                commands.entity(*client_entity).insert(sprite);
+                if let Ok(mut snapshot_buffer) = snapshot_query.get_mut(*client_entity) {
+                    snapshot_buffer.push(snapshot);
+                }
+                continue;
+            }
+
+            let client_entity = commands.spawn((
+                monster.monster_type,
+                sprite,
+                Transform::from_translation(snapshot.translation),
+                SnapshotBuffer::with_snapshot(snapshot),
+            )).id();
+            monster_mapping.0.insert(entity, client_entity);
+        }
+
+        monster_mapping.0.retain(|entity, client_entity| {
+            let active = active_monsters.contains(entity);
+            if !active {
+                commands.entity(*client_entity).despawn();
+            }
+            active
+        });
+    }
+}
+
 // Rust
 fn animate_weapon_fx(
     networked_entities: &NetworkedEntities,
@@ -132,53 +287,48 @@ fn animate_weapon_fx(
     commands: &mut Commands,
     texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
     weapon_fx_textures: &mut Res<WeaponFxTextures>,
-    weapon_fx_query: Query<&FxComponent>,
+    snapshot_query: &mut Query<&mut SnapshotBuffer>,
 ) {
-    for (i, entity_bits) in networked_entities.projectile_entities.iter().enumerate() {
-        let entity = Entity::from_bits(*entity_bits);
-
-        let translation = networked_entities.projectile_translations.get(i);
-        let aim_direction = networked_entities.player_aim_direction.get(i);
-
-        if let (Some(translation), Some(aim_direction)) = (translation, aim_direction) {
-            if !client_has_entity(entity, projectile_mapping) {
-                let client_entity = spawn_weapon_fx(
-                    commands,
-                    texture_atlas_layouts,
-                    weapon_fx_textures,
-                    (*translation).into(),
-                    &networked_entities.weapon_fx_texture_type,
-                    *aim_direction,
-                    false,
-                );
-                projectile_mapping.0.insert(entity, client_entity);
-            } else {
-                for _ in weapon_fx_query.iter() {
-                    if let Some(client_entity) = projectile_mapping.0.get(&entity) {
-                        let (image, layout) = handle_from_weapon_fx_texture(
-                            &networked_entities.weapon_fx_texture_type,
-                            texture_atlas_layouts,
-                            weapon_fx_textures,
-                        );
-
-                        commands.entity(*client_entity)
-                            .insert(Sprite {
-                                image,
-                                texture_atlas: Some(TextureAtlas {
-                                    layout,
-                                    index: networked_entities.projectile_sprite_index[i],
-                                }),
-                                flip_y: networked_entities.projectile_sprite_flip_y[i],
-                                ..default()
-                            })
-                            .insert(Transform {
-                                translation: (*translation).into(),
-                                scale: Vec3::splat(1.),
-                                rotation: Quat::from_rotation_z(networked_entities.player_aim_direction[i]),
-                            });
-                    }
-                }
+    for projectile in networked_entities.projectiles() {
+        let entity = Entity::from_bits(projectile.entity);
+        let snapshot = EntitySnapshot {
+            tick: networked_entities.tick,
+            translation: projectile.translation.into(),
+            rotation: projectile.aim_direction,
+        };
+
+        let sprite_frame = SpriteFrame {
+            index: projectile.sprite_index,
+            flip_y: projectile.sprite_flip_y,
+            ..Default::default()
+        };
+        let sprite = weapon_fx_sprite(
+            &projectile.weapon_fx_texture_type,
+            sprite_frame,
+            texture_atlas_layouts,
+            weapon_fx_textures,
+        );
+
+        if let Some(client_entity) = projectile_mapping.0.get(&entity) {
+            commands.entity(*client_entity).insert(sprite);
+
+            if let Ok(mut snapshot_buffer) = snapshot_query.get_mut(*client_entity) {
+                snapshot_buffer.push(snapshot);
             }
+        } else {
+            let client_entity = spawn_weapon_fx(
+                commands,
+                projectile.translation.into(),
+                &projectile.weapon_fx_texture_type,
+                projectile.aim_direction,
+                None,
+            );
+            commands.entity(client_entity).insert((
+                sprite,
+                SnapshotBuffer::with_snapshot(snapshot),
+                ProjectileOwner(projectile.owner),
+            ));
+            projectile_mapping.0.insert(entity, client_entity);
         }
     }
 
@@ -191,8 +341,8 @@ fn despawn_weapon_fx(
     projectile_mapping: &mut ResMut<ProjectileMapping>,
     commands: &mut Commands,
 ) {
-    let active_entities: std::collections::HashSet<_> = networked_entities.projectile_entities.iter()
-        .map(|bits| Entity::from_bits(*bits))
+    let active_entities: std::collections::HashSet<_> = networked_entities.projectiles()
+        .map(|projectile| Entity::from_bits(projectile.entity))
         .collect();
 
     let to_despawn: Vec<Entity> = projectile_mapping.0.keys()
@@ -206,6 +356,158 @@ fn despawn_weapon_fx(
         }
     }
 }
-fn client_has_entity(entity: Entity, network_mapping: &mut ProjectileMapping) -> bool {
-    network_mapping.0.contains_key(&entity)
-}
\ No newline at end of file
+
+/// Rebuilds pellet positions at the snapshot tick from the volley seed, as the server only replicates the volley.
+fn animate_pellet_volleys(
+    networked_entities: &NetworkedEntities,
+    pellet_mapping: &mut ResMut<PelletMapping>,
+    commands: &mut Commands,
+    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
+    weapon_fx_textures: &mut Res<WeaponFxTextures>,
+    snapshot_query: &mut Query<&mut SnapshotBuffer>,
+    tick_rate: f64,
+) {
+    let mut active_pellets = std::collections::HashSet::new();
+
+    for volley in networked_entities.pellet_volleys() {
+        let volley_entity = Entity::from_bits(volley.entity);
+        let elapsed_secs = (networked_entities.tick.saturating_sub(volley.fired_tick) as f64 / tick_rate) as f32;
+        let directions = projectile_directions(
+            volley.aim_direction,
+            volley.spread_degrees,
+            volley.pellet_count as u32,
+            volley.seed,
+        );
+
+        for (index, direction) in directions.into_iter().enumerate() {
+            let index = index as u8;
+            if volley.alive_pellets & (1 << index) == 0 {
+                continue;
+            }
+            active_pellets.insert((volley_entity, index));
+
+            let snapshot = EntitySnapshot {
+                tick: networked_entities.tick,
+                translation: projectile_position(volley.origin.into(), direction, volley.speed, elapsed_secs),
+                rotation: direction,
+            };
+
+            if let Some(client_entity) = pellet_mapping.0.get(&(volley_entity, index)) {
+                if let Ok(mut snapshot_buffer) = snapshot_query.get_mut(*client_entity) {
+                    snapshot_buffer.push(snapshot);
+                }
+                continue;
+            }
+
+            let sprite_frame = SpriteFrame {
+                index: WeaponFxTextureEntity::new(&volley.weapon_fx_texture_type).animation_indices.first as usize,
+                flip_y: !is_face_right(direction),
+                ..Default::default()
+            };
+            let client_entity = spawn_weapon_fx(
+                commands,
+                snapshot.translation,
+                &volley.weapon_fx_texture_type,
+                direction,
+                None,
+            );
+            commands.entity(client_entity).insert((
+                weapon_fx_sprite(&volley.weapon_fx_texture_type, sprite_frame, texture_atlas_layouts, weapon_fx_textures),
+                SnapshotBuffer::with_snapshot(snapshot),
+                ProjectileOwner(volley.owner),
+            ));
+            pellet_mapping.0.insert((volley_entity, index), client_entity);
+        }
+    }
+
+    pellet_mapping.0.retain(|pellet, client_entity| {
+        let active = active_pellets.contains(pellet);
+        if !active {
+            commands.entity(*client_entity).despawn();
+        }
+        active
+    });
+}
+
+const EXPLOSION_FX_SECS: f32 = 0.4;
+
+/// Expanding, fading disc shown where a grenade exploded.
+#[derive(Component)]
+pub struct ExplosionFx {
+    timer: Timer,
+    radius: f32,
+}
+
+/// Tints team players so teammates can be told apart from opponents wearing the same skin.
+/// Runs after `player_animation`, which inserts a fresh, untinted sprite with every snapshot.
+#[allow(clippy::type_complexity)]
+pub fn tint_team_players(mut player_query: Query<(&mut Sprite, &Team), Or<(Changed<Team>, Changed<Sprite>)>>) {
+    for (mut sprite, team) in player_query.iter_mut() {
+        sprite.color = match team {
+            Team::Red => Color::srgb(1.0, 0.7, 0.7),
+            Team::Blue => Color::srgb(0.7, 0.8, 1.0),
+        };
+    }
+}
+
+pub fn spawn_explosion_fx(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    translation: Vec3,
+    radius: f32,
+) {
+    commands.spawn((
+        ExplosionFx {
+            timer: Timer::from_seconds(EXPLOSION_FX_SECS, TimerMode::Once),
+            radius,
+        },
+        Mesh2d(meshes.add(Circle::new(1.0))),
+        MeshMaterial2d(materials.add(ColorMaterial::from(Color::srgba(1.0, 0.6, 0.1, 0.8)))),
+        Transform::from_translation(translation.with_z(translation.z + 1.0)).with_scale(Vec3::ZERO),
+    ));
+}
+
+pub fn animate_explosion_fx(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut query: Query<(Entity, &mut ExplosionFx, &mut Transform, &MeshMaterial2d<ColorMaterial>)>,
+) {
+    for (entity, mut explosion, mut transform, material) in query.iter_mut() {
+        if explosion.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let progress = explosion.timer.fraction();
+        transform.scale = Vec3::splat(explosion.radius * progress.sqrt());
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color.set_alpha(0.8 * (1.0 - progress));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::{App, Update};
+
+    #[test]
+    fn team_tint_survives_a_sprite_reinsert() {
+        let mut app = App::new();
+        app.add_systems(Update, tint_team_players);
+        let player = app.world_mut().spawn((Sprite::default(), Team::Blue)).id();
+        app.update();
+        let tint = app.world().get::<Sprite>(player).unwrap().color;
+        assert_ne!(tint, Color::WHITE);
+
+        app.world_mut().entity_mut(player).insert(Sprite::default());
+        app.update();
+        assert_eq!(app.world().get::<Sprite>(player).unwrap().color, tint);
+
+        app.world_mut().entity_mut(player).insert(Team::Red);
+        app.update();
+        assert_ne!(app.world().get::<Sprite>(player).unwrap().color, tint);
+    }
+}
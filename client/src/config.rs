@@ -0,0 +1,103 @@
+use crate::interpolation::DEFAULT_INTERPOLATION_DELAY_TICKS;
+use bevy::prelude::Resource;
+use game_core::config::{load_ron_config, CliArgs};
+use game_core::network::auth::CONNECT_TOKEN_PATH;
+use game_core::network::network::{PROTOCOL_ID, SERVER_TICK_RATE};
+use serde::Deserialize;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+pub const CLIENT_CONFIG_PATH: &str = "client.ron";
+
+/// Client settings, read from `client.ron` (or `--config <path>`) and overridden by command-line options.
+#[derive(Debug, Clone, Resource, Deserialize)]
+#[serde(default)]
+pub struct ClientSettings {
+    /// Server address for `--unsecure` connections; secure ones use the address in the connect token.
+    pub server_address: IpAddr,
+    pub port: u16,
+    /// Must match the server tick rate for the server clock estimate to hold.
+    pub tick_rate: f64,
+    /// Ticks remote entities are rendered behind the newest snapshot; more hides packet loss, less shows them sooner.
+    pub interpolation_delay_ticks: f64,
+    pub protocol_id: u64,
+    pub asset_path: String,
+    pub connect_token_path: String,
+    /// Connects without a connect token, for local development.
+    pub unsecure: bool,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            server_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 5000,
+            tick_rate: SERVER_TICK_RATE,
+            interpolation_delay_ticks: DEFAULT_INTERPOLATION_DELAY_TICKS,
+            protocol_id: PROTOCOL_ID,
+            asset_path: "../assets".to_string(),
+            connect_token_path: CONNECT_TOKEN_PATH.to_string(),
+            unsecure: false,
+        }
+    }
+}
+
+impl ClientSettings {
+    pub fn load(args: &CliArgs) -> Result<Self, String> {
+        let mut settings: Self = load_ron_config(args.value("--config"), CLIENT_CONFIG_PATH)?;
+
+        if let Some(server_address) = args.parse("--server")? {
+            settings.server_address = server_address;
+        }
+        if let Some(port) = args.parse("--port")? {
+            settings.port = port;
+        }
+        if let Some(tick_rate) = args.parse("--tick-rate")? {
+            settings.tick_rate = tick_rate;
+        }
+        if let Some(interpolation_delay_ticks) = args.parse("--interpolation-delay")? {
+            settings.interpolation_delay_ticks = interpolation_delay_ticks;
+        }
+        if let Some(protocol_id) = args.parse("--protocol-id")? {
+            settings.protocol_id = protocol_id;
+        }
+        if let Some(asset_path) = args.value("--assets") {
+            settings.asset_path = asset_path.to_string();
+        }
+        if let Some(connect_token_path) = args.value("--token") {
+            settings.connect_token_path = connect_token_path.to_string();
+        }
+        settings.unsecure |= args.flag("--unsecure");
+
+        if !(settings.tick_rate.is_finite() && settings.tick_rate > 0.0) {
+            return Err(format!("tick_rate invalide: {}", settings.tick_rate));
+        }
+        if !(settings.interpolation_delay_ticks.is_finite() && settings.interpolation_delay_ticks >= 0.0) {
+            return Err(format!("interpolation_delay_ticks invalide: {}", settings.interpolation_delay_ticks));
+        }
+
+        Ok(settings)
+    }
+
+    pub fn server_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.server_address, self.port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/client.example.ron");
+
+    fn load(args: &[&str]) -> Result<ClientSettings, String> {
+        let args = ["--config", EXAMPLE_CONFIG].iter().chain(args).map(|arg| arg.to_string()).collect();
+        ClientSettings::load(&CliArgs::new(args))
+    }
+
+    #[test]
+    fn interpolation_delay_comes_from_the_config_or_the_command_line() {
+        assert_eq!(load(&[]).unwrap().interpolation_delay_ticks, 6.0);
+        assert_eq!(load(&["--interpolation-delay", "3"]).unwrap().interpolation_delay_ticks, 3.0);
+        assert!(load(&["--interpolation-delay", "-1"]).is_err());
+    }
+}
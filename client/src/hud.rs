@@ -0,0 +1,321 @@
+use bevy::input::ButtonInput;
+use bevy::prelude::{Commands, KeyCode, Res, ResMut, Resource, Time, Timer, TimerMode};
+use bevy_renet2::prelude::ClientId;
+use bevy_egui::{egui, EguiContexts};
+use game_core::game_mode::component::{MatchPhase, ScoreEntry, ScoreOwner, ScoreboardEntry, Team};
+use game_core::game_mode::flag::FlagEvent;
+use game_core::monster::wave::WaveProgress;
+use game_core::player::component::CurrentClientId;
+use game_core::weapon::texture::WeaponTextureType;
+use std::collections::VecDeque;
+
+/// Last survival mode state received; absent outside the survival mode.
+#[derive(Debug, Resource)]
+pub struct WaveHud {
+    pub wave: u32,
+    pub progress: WaveProgress,
+}
+
+/// Wave number, remaining monsters and countdowns, counted down locally between server updates.
+pub fn wave_hud(
+    mut egui_contexts: EguiContexts,
+    time: Res<Time>,
+    mut hud: ResMut<WaveHud>,
+) {
+    let elapsed = time.delta_secs();
+    match &mut hud.progress {
+        WaveProgress::Intermission { next_wave_in_secs: secs } | WaveProgress::GameOver { restart_in_secs: secs } => {
+            *secs = (*secs - elapsed).max(0.0);
+        }
+        WaveProgress::InProgress { .. } => {}
+    }
+
+    let Ok(ctx) = egui_contexts.ctx_mut() else { return };
+    egui::Area::new(egui::Id::new("wave_hud"))
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::new(0.0, 10.0))
+        .show(ctx, |ui| {
+            match hud.progress {
+                WaveProgress::Intermission { next_wave_in_secs } => {
+                    if hud.wave > 0 {
+                        ui.heading(format!("Vague {} terminée", hud.wave));
+                    }
+                    ui.label(format!("Prochaine vague dans {:.0} s", next_wave_in_secs.ceil()));
+                }
+                WaveProgress::InProgress { remaining_monsters } => {
+                    ui.heading(format!("Vague {}", hud.wave));
+                    ui.label(format!("Monstres restants : {remaining_monsters}"));
+                }
+                WaveProgress::GameOver { restart_in_secs } => {
+                    ui.heading(format!("Partie terminée à la vague {}", hud.wave));
+                    ui.label(format!("Nouvelle partie dans {:.0} s", restart_in_secs.ceil()));
+                }
+            }
+        });
+}
+
+/// Last `MatchState` received; absent in the survival mode.
+#[derive(Debug, Resource)]
+pub struct MatchHud {
+    pub mode: String,
+    pub phase: MatchPhase,
+    pub remaining_secs: Option<f32>,
+    pub score_limit: u32,
+    pub scores: Vec<ScoreEntry>,
+    pub winner: Option<ScoreOwner>,
+}
+
+/// Match phase, countdown and scores in the top right corner.
+pub fn match_hud(
+    mut egui_contexts: EguiContexts,
+    time: Res<Time>,
+    client_id: Res<CurrentClientId>,
+    mut hud: ResMut<MatchHud>,
+) {
+    let elapsed = time.delta_secs();
+    if let Some(remaining_secs) = hud.remaining_secs.as_mut() {
+        *remaining_secs = (*remaining_secs - elapsed).max(0.0);
+    }
+
+    let Ok(ctx) = egui_contexts.ctx_mut() else { return };
+    egui::Area::new(egui::Id::new("match_hud"))
+        .anchor(egui::Align2::RIGHT_TOP, egui::Vec2::new(-10.0, 10.0))
+        .show(ctx, |ui| {
+            let phase = match hud.phase {
+                MatchPhase::Warmup => "Échauffement",
+                MatchPhase::InProgress => "Match en cours",
+                MatchPhase::Ended => "Fin du match",
+            };
+            ui.heading(format!("{} — {phase}", hud.mode));
+
+            match (hud.phase, hud.remaining_secs) {
+                (MatchPhase::Warmup, None) => {
+                    ui.label("En attente de joueurs");
+                }
+                (MatchPhase::Warmup, Some(secs)) => {
+                    ui.label(format!("Début dans {:.0} s", secs.ceil()));
+                }
+                (MatchPhase::InProgress, Some(secs)) => {
+                    ui.label(format!("Temps restant {}:{:02}", secs as u32 / 60, secs as u32 % 60));
+                }
+                (MatchPhase::Ended, Some(secs)) => {
+                    match hud.winner {
+                        Some(winner) => ui.label(format!("Victoire : {}", score_owner_label(winner, client_id.0))),
+                        None => ui.label("Égalité"),
+                    };
+                    ui.label(format!("Prochain match dans {:.0} s", secs.ceil()));
+                }
+                _ => {}
+            }
+
+            if hud.score_limit > 0 {
+                ui.label(format!("Limite de score : {}", hud.score_limit));
+            }
+            for entry in &hud.scores {
+                ui.label(format!("{} : {}", score_owner_label(entry.owner, client_id.0), entry.score));
+            }
+        });
+}
+
+const ANNOUNCEMENT_SECS: f32 = 3.0;
+const KILL_FEED_SECS: f32 = 6.0;
+const KILL_FEED_LEN: usize = 5;
+const SHOW_SCOREBOARD: KeyCode = KeyCode::Tab;
+
+/// Short-lived message in the middle of the screen, replaced by the next one.
+#[derive(Debug, Resource)]
+pub struct Announcement {
+    pub text: String,
+    timer: Timer,
+}
+
+impl Announcement {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            timer: Timer::from_seconds(ANNOUNCEMENT_SECS, TimerMode::Once),
+        }
+    }
+}
+
+pub fn announcement_hud(
+    mut commands: Commands,
+    mut egui_contexts: EguiContexts,
+    time: Res<Time>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if announcement.timer.tick(time.delta()).finished() {
+        commands.remove_resource::<Announcement>();
+        return;
+    }
+
+    let Ok(ctx) = egui_contexts.ctx_mut() else { return };
+    egui::Area::new(egui::Id::new("announcement"))
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::new(0.0, -120.0))
+        .show(ctx, |ui| {
+            ui.heading(&announcement.text);
+        });
+}
+
+pub fn flag_announcement(team: Team, event: &FlagEvent, own_client_id: u64) -> Option<String> {
+    let flag = match team {
+        Team::Red => "le drapeau rouge",
+        Team::Blue => "le drapeau bleu",
+    };
+    let player = |client_id| score_owner_label(ScoreOwner::Player(client_id), own_client_id);
+    let announcement = match event {
+        FlagEvent::Taken { by } => format!("{} a pris {flag}", player(*by)),
+        FlagEvent::Dropped { by, .. } => format!("{} a perdu {flag}", player(*by)),
+        FlagEvent::Returned { by: Some(by), .. } => format!("{} a ramené {flag}", player(*by)),
+        FlagEvent::Returned { by: None, .. } => format!("Retour de {flag} à sa base"),
+        FlagEvent::Captured { by } => format!("{} a capturé {flag} !", player(*by)),
+        // The match start is already announced.
+        FlagEvent::Reset => return None,
+    };
+    Some(announcement)
+}
+
+/// Last `Scoreboard` received.
+#[derive(Debug, Resource)]
+pub struct ScoreboardHud {
+    pub players: Vec<ScoreboardEntry>,
+}
+
+impl ScoreboardHud {
+    fn name(&self, client_id: ClientId) -> Option<&str> {
+        self.players.iter()
+            .find(|entry| entry.id == client_id)
+            .map(|entry| entry.name.as_str())
+    }
+}
+
+/// Statistics of every player while Tab is held, teammates grouped together.
+pub fn scoreboard_hud(
+    mut egui_contexts: EguiContexts,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    client_id: Res<CurrentClientId>,
+    hud: Res<ScoreboardHud>,
+) {
+    if !keyboard_input.pressed(SHOW_SCOREBOARD) {
+        return;
+    }
+
+    let mut players: Vec<&ScoreboardEntry> = hud.players.iter().collect();
+    players.sort_by_key(|entry| entry.team);
+
+    let Ok(ctx) = egui_contexts.ctx_mut() else { return };
+    egui::Window::new("Tableau des scores")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Grid::new("scoreboard").striped(true).show(ui, |ui| {
+                for header in ["Joueur", "Équipe", "Éliminations", "Morts", "Assistances", "Dégâts"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+
+                for entry in players {
+                    let name = if entry.id == client_id.0 { format!("{} (vous)", entry.name) } else { entry.name.clone() };
+                    ui.label(name);
+                    ui.label(entry.team.map_or("", team_label));
+                    ui.label(entry.stats.kills.to_string());
+                    ui.label(entry.stats.deaths.to_string());
+                    ui.label(entry.stats.assists.to_string());
+                    ui.label(entry.stats.damage_dealt.to_string());
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+#[derive(Debug)]
+pub struct KillFeedEntry {
+    pub victim: ClientId,
+    pub killer: Option<ClientId>,
+    pub weapon: Option<WeaponTextureType>,
+    pub assists: Vec<ClientId>,
+    timer: Timer,
+}
+
+/// Most recent deaths, newest last.
+#[derive(Debug, Default, Resource)]
+pub struct KillFeed(VecDeque<KillFeedEntry>);
+
+impl KillFeed {
+    pub fn push(&mut self, victim: ClientId, killer: Option<ClientId>, weapon: Option<WeaponTextureType>, assists: Vec<ClientId>) {
+        self.0.push_back(KillFeedEntry {
+            victim,
+            killer,
+            weapon,
+            assists,
+            timer: Timer::from_seconds(KILL_FEED_SECS, TimerMode::Once),
+        });
+        while self.0.len() > KILL_FEED_LEN {
+            self.0.pop_front();
+        }
+    }
+}
+
+pub fn kill_feed_hud(
+    mut egui_contexts: EguiContexts,
+    time: Res<Time>,
+    client_id: Res<CurrentClientId>,
+    scoreboard: Option<Res<ScoreboardHud>>,
+    mut kill_feed: ResMut<KillFeed>,
+) {
+    kill_feed.0.retain_mut(|entry| !entry.timer.tick(time.delta()).finished());
+    if kill_feed.0.is_empty() {
+        return;
+    }
+
+    let player = |id: ClientId| {
+        let name = scoreboard.as_ref()
+            .and_then(|scoreboard| scoreboard.name(id))
+            .map_or_else(|| format!("Joueur {id}"), str::to_string);
+        if id == client_id.0 { format!("{name} (vous)") } else { name }
+    };
+
+    let Ok(ctx) = egui_contexts.ctx_mut() else { return };
+    egui::Area::new(egui::Id::new("kill_feed"))
+        .anchor(egui::Align2::LEFT_TOP, egui::Vec2::new(10.0, 10.0))
+        .show(ctx, |ui| {
+            for entry in &kill_feed.0 {
+                let mut line = match (entry.killer, &entry.weapon) {
+                    (Some(killer), _) if killer == entry.victim => format!("{} s'est éliminé", player(killer)),
+                    (Some(killer), Some(weapon)) => format!("{} a éliminé {} ({})", player(killer), player(entry.victim), weapon_label(weapon)),
+                    (Some(killer), None) => format!("{} a éliminé {}", player(killer), player(entry.victim)),
+                    (None, _) => format!("{} est mort", player(entry.victim)),
+                };
+                if !entry.assists.is_empty() {
+                    let assists: Vec<String> = entry.assists.iter().map(|id| player(*id)).collect();
+                    line.push_str(&format!(", aidé de {}", assists.join(", ")));
+                }
+                ui.label(line);
+            }
+        });
+}
+
+fn weapon_label(weapon: &WeaponTextureType) -> &'static str {
+    match weapon {
+        WeaponTextureType::Pistol => "pistolet",
+        WeaponTextureType::Shotgun => "fusil à pompe",
+        WeaponTextureType::Rifle => "fusil",
+        WeaponTextureType::GrenadeLauncher => "lance-grenades",
+    }
+}
+
+fn team_label(team: Team) -> &'static str {
+    match team {
+        Team::Red => "Rouge",
+        Team::Blue => "Bleue",
+    }
+}
+
+fn score_owner_label(owner: ScoreOwner, own_client_id: u64) -> String {
+    match owner {
+        ScoreOwner::Player(client_id) if client_id == own_client_id => format!("Joueur {client_id} (vous)"),
+        ScoreOwner::Player(client_id) => format!("Joueur {client_id}"),
+        ScoreOwner::Team(Team::Red) => "Équipe rouge".to_string(),
+        ScoreOwner::Team(Team::Blue) => "Équipe bleue".to_string(),
+    }
+}
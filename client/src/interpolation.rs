@@ -0,0 +1,87 @@
+use crate::network::clock::ServerClock;
+use bevy::math::{Quat, Vec3};
+use bevy::prelude::{Component, Query, Res, Resource, Time, Transform};
+use std::collections::VecDeque;
+
+const MAX_BUFFERED_SNAPSHOTS: usize = 32;
+
+pub const DEFAULT_INTERPOLATION_DELAY_TICKS: f64 = 6.0;
+
+#[derive(Resource)]
+pub struct InterpolationConfig {
+    /// How far behind the newest received tick remote entities are rendered.
+    pub delay_ticks: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EntitySnapshot {
+    pub tick: u64,
+    pub translation: Vec3,
+    pub rotation: f32,
+}
+
+#[derive(Component, Debug, Default)]
+pub struct SnapshotBuffer(VecDeque<EntitySnapshot>);
+
+impl SnapshotBuffer {
+    pub fn with_snapshot(snapshot: EntitySnapshot) -> Self {
+        Self(VecDeque::from([snapshot]))
+    }
+
+    pub fn push(&mut self, snapshot: EntitySnapshot) {
+        match self.0.iter().position(|s| s.tick >= snapshot.tick) {
+            Some(index) if self.0[index].tick == snapshot.tick => return,
+            Some(index) => self.0.insert(index, snapshot),
+            None => self.0.push_back(snapshot),
+        }
+
+        while self.0.len() > MAX_BUFFERED_SNAPSHOTS {
+            self.0.pop_front();
+        }
+    }
+
+    /// Interpolates between the two snapshots bracketing `render_tick`,
+    /// holding the first or last snapshot when outside the buffered range.
+    pub fn sample(&self, render_tick: f64) -> Option<(Vec3, Quat)> {
+        let first = self.0.front()?;
+        if render_tick <= first.tick as f64 {
+            return Some((first.translation, Quat::from_rotation_z(first.rotation)));
+        }
+
+        for (from, to) in self.0.iter().zip(self.0.iter().skip(1)) {
+            if render_tick <= to.tick as f64 {
+                let alpha = ((render_tick - from.tick as f64) / (to.tick - from.tick) as f64) as f32;
+                let translation = from.translation.lerp(to.translation, alpha);
+                let rotation = Quat::from_rotation_z(from.rotation).slerp(Quat::from_rotation_z(to.rotation), alpha);
+                return Some((translation, rotation));
+            }
+        }
+
+        let last = self.0.back()?;
+        Some((last.translation, Quat::from_rotation_z(last.rotation)))
+    }
+
+    fn discard_before(&mut self, render_tick: f64) {
+        while self.0.len() > 2 && self.0[1].tick as f64 <= render_tick {
+            self.0.pop_front();
+        }
+    }
+}
+
+pub fn interpolate_snapshots(
+    time: Res<Time>,
+    config: Res<InterpolationConfig>,
+    clock: Res<ServerClock>,
+    mut query: Query<(&mut SnapshotBuffer, &mut Transform)>,
+) {
+    let Some(received_tick) = clock.received_tick(time.elapsed_secs_f64()) else { return };
+    let render_tick = received_tick - config.delay_ticks;
+
+    for (mut buffer, mut transform) in query.iter_mut() {
+        buffer.discard_before(render_tick);
+        if let Some((translation, rotation)) = buffer.sample(render_tick) {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::asset::{AssetServer, Assets, RecursiveDependencyLoadState};
+use bevy::log::{error, info};
+use bevy::image::TextureAtlasLayout;
+use bevy::prelude::{ColorMaterial, Commands, Entity, Mesh, Query, Res, ResMut, With};
+use game_core::decor::component::LevelEntity;
+use game_core::decor::level::{LevelDefinition, LevelHandle};
+use game_core::decor::system::spawn_level_visuals;
+
+/// Replaces the drawn level once the one requested by the server is loaded.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_level(
+    mut commands: Commands,
+    level_handle: Option<ResMut<LevelHandle>>,
+    levels: Res<Assets<LevelDefinition>>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    level_entities: Query<Entity, With<LevelEntity>>,
+) {
+    let Some(mut level_handle) = level_handle else {
+        return;
+    };
+    if level_handle.spawned {
+        return;
+    }
+
+    let Some(level) = levels.get(&level_handle.handle) else {
+        if let Some(RecursiveDependencyLoadState::Failed(e)) = asset_server.get_recursive_dependency_load_state(&level_handle.handle) {
+            error!("Impossible de charger le niveau {}: {e}", level_handle.name);
+            level_handle.spawned = true;
+        }
+        return;
+    };
+
+    for entity in &level_entities {
+        commands.entity(entity).despawn();
+    }
+    spawn_level_visuals(&mut commands, &mut meshes, &mut materials, &mut texture_atlas_layouts, &asset_server, level);
+    level_handle.spawned = true;
+    info!("Level {} loaded", level_handle.name);
+}
@@ -1,5 +1,10 @@
+pub mod config;
+pub mod hud;
+pub mod interpolation;
+pub mod level;
 pub mod network;
 pub mod player_input;
 pub mod animation;
 pub mod plugin;
+pub mod prediction;
 
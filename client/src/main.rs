@@ -2,9 +2,16 @@ use bevy::prelude::*;
 use bevy_egui::EguiPlugin;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_renet2::prelude::RenetClientPlugin;
+use client::config::ClientSettings;
 use client::plugin::ClientPlugin;
+use game_core::config::CliArgs;
 
 fn main() {
+    let settings = ClientSettings::load(&CliArgs::from_env()).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+
     let mut app = App::new();
     app.add_plugins(DefaultPlugins
         .set(WindowPlugin {
@@ -16,7 +23,7 @@ fn main() {
             ..default()
         }).set(ImagePlugin::default_nearest())
         .set(AssetPlugin {
-            file_path: "../assets".into(),
+            file_path: settings.asset_path.clone(),
             ..default()
         })
     );
@@ -24,7 +31,7 @@ fn main() {
     app.add_plugins(RenetClientPlugin);
     app.add_plugins(EguiPlugin::default());
     app.add_plugins(WorldInspectorPlugin::new());
-    app.add_plugins(ClientPlugin);
+    app.add_plugins(ClientPlugin { settings });
     app.run();
 }
 
@@ -1,7 +1,10 @@
 use bevy::prelude::{Entity, Resource, SystemSet};
 use bevy_renet2::prelude::ClientId;
+use game_core::network::network::NetworkedEntities;
 use std::collections::HashMap;
 
+pub mod clock;
+pub mod connection;
 pub mod system;
 
 #[derive(Default, Resource)]
@@ -10,6 +13,25 @@ pub struct PlayerMapping(pub(crate) HashMap<Entity, Entity>);
 #[derive(Default, Resource)]
 pub struct ProjectileMapping(pub(crate) HashMap<Entity, Entity>);
 
+/// Client entity of each pellet, keyed by server volley entity and pellet index.
+#[derive(Default, Resource)]
+pub struct PelletMapping(pub(crate) HashMap<(Entity, u8), Entity>);
+
+#[derive(Default, Resource)]
+pub struct MonsterMapping(pub(crate) HashMap<Entity, Entity>);
+
+/// Client entity of each capture-the-flag flag, keyed by server entity.
+#[derive(Default, Resource)]
+pub struct FlagMapping(pub(crate) HashMap<Entity, Entity>);
+
+/// Client entity of each active pickup, keyed by server entity.
+#[derive(Default, Resource)]
+pub struct PickupMapping(pub(crate) HashMap<Entity, Entity>);
+
+/// Snapshots decoded this frame, in arrival order, waiting to be applied.
+#[derive(Default, Resource)]
+pub struct ReceivedSnapshots(pub(crate) Vec<NetworkedEntities>);
+
 #[derive(Debug)]
 struct PlayerInfo {
     client_entity: Entity,
@@ -0,0 +1,60 @@
+use bevy::prelude::Resource;
+use game_core::network::network::SERVER_TICK_RATE;
+
+const CLOCK_SMOOTHING: f64 = 0.1;
+
+/// Client-side estimate of the server tick, derived from tick-stamped
+/// snapshots and smoothed against jitter.
+#[derive(Resource, Debug)]
+pub struct ServerClock {
+    tick_rate: f64,
+    latest_snapshot_tick: Option<u64>,
+    offset_ticks: Option<f64>,
+}
+
+impl Default for ServerClock {
+    fn default() -> Self {
+        Self::new(SERVER_TICK_RATE)
+    }
+}
+
+impl ServerClock {
+    pub fn new(tick_rate: f64) -> Self {
+        Self {
+            tick_rate,
+            latest_snapshot_tick: None,
+            offset_ticks: None,
+        }
+    }
+
+    pub fn tick_rate(&self) -> f64 {
+        self.tick_rate
+    }
+
+    pub fn observe(&mut self, tick: u64, now_secs: f64) {
+        let sample = tick as f64 - now_secs * self.tick_rate;
+        self.offset_ticks = Some(match self.offset_ticks {
+            // A jump of more than a second means the server restarted or we stalled: resync.
+            Some(offset) if (sample - offset).abs() < self.tick_rate => offset + (sample - offset) * CLOCK_SMOOTHING,
+            _ => sample,
+        });
+    }
+
+    /// Returns `false` for duplicate or out-of-order snapshots.
+    pub fn accept_snapshot(&mut self, tick: u64) -> bool {
+        if self.latest_snapshot_tick.is_some_and(|latest| tick <= latest) {
+            return false;
+        }
+        self.latest_snapshot_tick = Some(tick);
+        true
+    }
+
+    pub fn latest_snapshot_tick(&self) -> Option<u64> {
+        self.latest_snapshot_tick
+    }
+
+    /// Tick of the newest packet the client should have received by now.
+    pub fn received_tick(&self, now_secs: f64) -> Option<f64> {
+        self.offset_ticks.map(|offset| offset + now_secs * self.tick_rate)
+    }
+}
@@ -0,0 +1,169 @@
+use crate::config::ClientSettings;
+use crate::hud::{Announcement, KillFeed, MatchHud, ScoreboardHud, WaveHud};
+use crate::interpolation::SnapshotBuffer;
+use crate::network::clock::ServerClock;
+use crate::network::{ClientLobby, FlagMapping, MonsterMapping, PelletMapping, PickupMapping, PlayerMapping, ProjectileMapping, ReceivedSnapshots};
+use crate::prediction::{InputHistory, PendingReconciliation};
+use bevy::log::{error, info};
+use bevy::prelude::{Commands, Entity, EventReader, Query, Res, Resource, With};
+use bevy_egui::{egui, EguiContexts};
+use bevy_renet2::netcode::{ClientAuthentication, NetcodeClientTransport, NetcodeError, NetcodeTransportError};
+use bevy_renet2::prelude::RenetClient;
+use game_core::network::auth::{is_connect_token_expired, new_client_id, read_connect_token};
+use game_core::network::network::connection_config;
+use game_core::network::snapshot::SnapshotHistory;
+use game_core::network::utils::{get_current_time, get_native_socket, get_socket};
+use game_core::player::component::{CurrentClientId, PlayerNetwork};
+
+/// Present while the connection to the server is lost, until the player reconnects.
+#[derive(Debug, Resource)]
+pub struct Disconnected {
+    pub reason: String,
+}
+
+pub fn new_client_connection(
+    settings: &ClientSettings,
+) -> Result<(RenetClient, NetcodeClientTransport, CurrentClientId), NetcodeError> {
+    let client = RenetClient::new(connection_config(), false);
+
+    let socket_addr = "127.0.0.1:0".parse().expect("Échec du parsing de l'adresse du socket");
+    let socket = get_socket(socket_addr);
+
+    let current_time: std::time::Duration = get_current_time();
+
+    let (client_id, authentication) = if settings.unsecure {
+        let client_id = new_client_id();
+        (client_id, ClientAuthentication::Unsecure {
+            client_id,
+            protocol_id: settings.protocol_id,
+            socket_id: 0,
+            server_addr: settings.server_addr(),
+            user_data: None,
+        })
+    } else {
+        let connect_token = read_connect_token(&settings.connect_token_path)?;
+        if is_connect_token_expired(&connect_token, current_time) {
+            return Err(NetcodeError::Expired);
+        }
+        (connect_token.client_id, ClientAuthentication::Secure { connect_token })
+    };
+
+    let native_socket = get_native_socket(socket);
+
+    let transport = NetcodeClientTransport::new(current_time, authentication, native_socket)?;
+
+    Ok((client, transport, CurrentClientId(client_id)))
+}
+
+pub fn detect_disconnection(
+    mut commands: Commands,
+    mut transport_errors: EventReader<NetcodeTransportError>,
+    client: Res<RenetClient>,
+    disconnected: Option<Res<Disconnected>>,
+) {
+    let mut reason = None;
+    for e in transport_errors.read() {
+        error!("Erreur de transport réseau: {e}");
+        reason = Some(e.to_string());
+    }
+
+    if disconnected.is_some() {
+        return;
+    }
+
+    if let Some(disconnect_reason) = client.disconnect_reason() {
+        reason.get_or_insert_with(|| disconnect_reason.to_string());
+    }
+
+    if let Some(reason) = reason {
+        error!("Connexion au serveur perdue: {reason}");
+        commands.insert_resource(Disconnected { reason });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn disconnected_window(
+    mut commands: Commands,
+    mut egui_contexts: EguiContexts,
+    disconnected: Res<Disconnected>,
+    settings: Res<ClientSettings>,
+    lobby: Res<ClientLobby>,
+    projectile_mapping: Res<ProjectileMapping>,
+    pellet_mapping: Res<PelletMapping>,
+    pickup_mapping: Res<PickupMapping>,
+    monster_mapping: Res<MonsterMapping>,
+    flag_mapping: Res<FlagMapping>,
+    remote_entities: Query<Entity, With<SnapshotBuffer>>,
+    player_entities: Query<Entity, With<PlayerNetwork>>,
+) {
+    let Ok(ctx) = egui_contexts.ctx_mut() else { return };
+
+    let mut reconnect = false;
+    egui::Window::new("Déconnecté")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(format!("Connexion au serveur perdue: {}", disconnected.reason));
+            reconnect = ui.button("Se reconnecter ?").clicked();
+        });
+
+    if !reconnect {
+        return;
+    }
+
+    let (client, transport, client_id) = match new_client_connection(&settings) {
+        Ok(connection) => connection,
+        Err(NetcodeError::Expired) => {
+            error!(
+                "Échec de la reconnexion: le jeton de connexion {} a expiré, demandez-en un nouveau (server issue-token)",
+                settings.connect_token_path,
+            );
+            return;
+        }
+        Err(e) => {
+            error!("Échec de la reconnexion: {e}");
+            return;
+        }
+    };
+
+    let stale_entities = lobby.players
+        .values()
+        .map(|player_info| player_info.client_entity)
+        .chain(projectile_mapping.0.values().copied())
+        .chain(pellet_mapping.0.values().copied())
+        .chain(pickup_mapping.0.values().copied())
+        .chain(monster_mapping.0.values().copied())
+        .chain(flag_mapping.0.values().copied())
+        .chain(remote_entities.iter())
+        .chain(player_entities.iter());
+    for entity in stale_entities {
+        if let Ok(mut entity_commands) = commands.get_entity(entity) {
+            entity_commands.try_despawn();
+        }
+    }
+
+    commands.insert_resource(ClientLobby::default());
+    commands.insert_resource(PlayerMapping::default());
+    commands.insert_resource(ProjectileMapping::default());
+    commands.insert_resource(PelletMapping::default());
+    commands.insert_resource(PickupMapping::default());
+    commands.insert_resource(MonsterMapping::default());
+    commands.insert_resource(FlagMapping::default());
+    commands.insert_resource(InputHistory::default());
+    commands.insert_resource(PendingReconciliation::default());
+    commands.insert_resource(ServerClock::new(settings.tick_rate));
+    commands.insert_resource(ReceivedSnapshots::default());
+    commands.insert_resource(SnapshotHistory::default());
+    commands.insert_resource(client);
+    commands.insert_resource(transport);
+    commands.insert_resource(client_id);
+    commands.remove_resource::<WaveHud>();
+    commands.remove_resource::<MatchHud>();
+    commands.remove_resource::<Announcement>();
+    commands.remove_resource::<ScoreboardHud>();
+    commands.insert_resource(KillFeed::default());
+    commands.remove_resource::<Disconnected>();
+
+    info!("Reconnexion au serveur en cours");
+}
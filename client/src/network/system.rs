@@ -1,15 +1,25 @@
-use crate::network::{ClientLobby, PlayerInfo, PlayerMapping};
-use bevy::asset::Assets;
+use crate::animation::spawn_explosion_fx;
+use crate::hud::{flag_announcement, Announcement, KillFeed, MatchHud, ScoreboardHud, WaveHud};
+use crate::interpolation::SnapshotBuffer;
+use crate::network::clock::ServerClock;
+use crate::network::{ClientLobby, PlayerInfo, PlayerMapping, ReceivedSnapshots};
+use bevy::asset::{AssetServer, Assets};
 
 use bevy::image::TextureAtlasLayout;
-use bevy::prelude::{info, ColorMaterial, Commands, Entity, Mesh, Res, ResMut};
+use bevy::prelude::{error, info, ColorMaterial, Commands, Entity, Mesh, Query, Res, ResMut, Time, Transform, Visibility};
+use bevy_rapier2d::dynamics::Velocity;
 use bevy_renet2::prelude::RenetClient;
-use game_core::network::network::{ServerChannel, ServerMessages};
-use game_core::player::command::{spawn_player_entity, SpawnPlayerParams};
-use game_core::player::component::{ControlledPlayer, CurrentClientId};
+use game_core::decor::system::load_level;
+use game_core::game_mode::flag::{FlagCarrier, FlagEvent};
+use game_core::network::network::{ClientChannel, ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::network::snapshot::{decode_snapshot, SnapshotAck, SnapshotDelta, SnapshotHistory};
+use game_core::pickup::component::Pickup;
+use game_core::player::command::{player_sprite, spawn_player_entity, SpawnPlayerParams};
+use game_core::player::component::{ControlledPlayer, CurrentClientId, Dead, Health, Invulnerable, PlayerWeaponSelected};
 use game_core::player::texture::{PlayerTextureEntity, PlayerTextureType, PlayerTextures};
-use game_core::weapon::command::spawn_weapon_entity;
-use game_core::weapon::texture::{WeaponTextureType, WeaponTextures};
+use game_core::texture::component::SpriteFrame;
+use game_core::weapon::command::{spawn_weapon_entity, weapon_sprite};
+use game_core::weapon::texture::{WeaponTextureEntity, WeaponTextureType, WeaponTextures};
 
 #[allow(clippy::too_many_arguments)]
 pub fn client_event(
@@ -21,20 +31,27 @@ pub fn client_event(
     mut lobby: ResMut<ClientLobby>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut network_mapping: ResMut<PlayerMapping>,
+    mut health_query: Query<&mut Health>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut kill_feed: ResMut<KillFeed>,
+    asset_server: Res<AssetServer>,
 ) {
     let client_id = client_id.0;
     while let Some(message) = client.receive_message(ServerChannel::ServerMessages) {
-        let server_message = match bincode::deserialize(&message) {
+        // The tick of reliable messages is not fed to `ServerClock`: they may be resent late and skew its offset.
+        let TickedServerMessage { message: server_message, .. } = match bincode::deserialize(&message) {
             Ok(msg) => msg,
             Err(e) => {
-                info!("Erreur de désérialisation du message serveur: {:?}", e);
+                error!("Erreur de désérialisation du message serveur: {:?}", e);
                 continue;
             }
         };
-
         match server_message {
+            ServerMessages::LoadLevel { name } => {
+                info!("Loading level {name}");
+                commands.insert_resource(load_level(&asset_server, &name));
+            }
             ServerMessages::PlayerCreate { id, translation, entity, player_texture_entity_type, weapon_texture_entity_type } => {
                 info!("Player created: {id} at {translation:?}");
 
@@ -46,13 +63,13 @@ pub fn client_event(
                     &mut commands,
                     &mut texture_atlas_layouts,
                     &mut player_textures,
-                    &mut meshes,
-                    &mut materials,
                     &mut weapon_textures,
                 );
 
                 if client_id == id {
-                    commands.entity(client_entity).insert(ControlledPlayer);
+                    commands.entity(client_entity).insert((ControlledPlayer, Velocity::zero()));
+                } else {
+                    commands.entity(client_entity).insert(SnapshotBuffer::default());
                 }
 
                 let player_info = PlayerInfo {
@@ -73,10 +90,159 @@ pub fn client_event(
                     network_mapping.0.remove(&server_entity);
                 }
             }
+            ServerMessages::PlayerHit { attacker, victim, damage, health } => {
+                info!("Player {victim} hit by {attacker} for {damage}, {health} left");
+                if let Some(player_info) = lobby.players.get(&victim)
+                    && let Ok(mut victim_health) = health_query.get_mut(player_info.client_entity)
+                {
+                    victim_health.current = health;
+                }
+            }
+            ServerMessages::PlayerHitByMonster { victim, damage, health } => {
+                info!("Player {victim} hit by a monster for {damage}, {health} left");
+                if let Some(player_info) = lobby.players.get(&victim)
+                    && let Ok(mut victim_health) = health_query.get_mut(player_info.client_entity)
+                {
+                    victim_health.current = health;
+                }
+            }
+            ServerMessages::MonsterKilled { entity, killer } => {
+                match killer {
+                    Some(killer) => info!("Monster {entity} killed by {killer}"),
+                    None => info!("Monster {entity} died"),
+                }
+            }
+            ServerMessages::MatchState { mode, phase, remaining_secs, score_limit, scores, winner } => {
+                info!("{mode}: {phase:?}, scores {scores:?}");
+                commands.insert_resource(MatchHud { mode, phase, remaining_secs, score_limit, scores, winner });
+            }
+            ServerMessages::TeamChanged { id, team } => {
+                info!("Player {id} joined team {team:?}");
+                if let Some(player_info) = lobby.players.get(&id) {
+                    commands.entity(player_info.client_entity).insert(team);
+                }
+            }
+            ServerMessages::FlagChanged { team, event } => {
+                info!("Flag {team:?}: {event:?}");
+                if let Some(announcement) = flag_announcement(team, &event, client_id) {
+                    commands.insert_resource(Announcement::new(announcement));
+                }
+                match event {
+                    FlagEvent::Taken { by } => {
+                        if let Some(player_info) = lobby.players.get(&by) {
+                            commands.entity(player_info.client_entity).insert(FlagCarrier { team });
+                        }
+                    }
+                    FlagEvent::Dropped { by, .. } | FlagEvent::Captured { by } | FlagEvent::Returned { carrier: Some(by), .. } => {
+                        if let Some(player_info) = lobby.players.get(&by) {
+                            commands.entity(player_info.client_entity).remove::<FlagCarrier>();
+                        }
+                    }
+                    FlagEvent::Returned { carrier: None, .. } => {}
+                    FlagEvent::Reset => {
+                        for player_info in lobby.players.values() {
+                            commands.entity(player_info.client_entity).remove::<FlagCarrier>();
+                        }
+                    }
+                }
+            }
+            ServerMessages::Scoreboard { players } => {
+                commands.insert_resource(ScoreboardHud { players });
+            }
+            ServerMessages::WaveStatus { wave, progress } => {
+                info!("Wave {wave}: {progress:?}");
+                commands.insert_resource(WaveHud { wave, progress });
+            }
+            ServerMessages::PickupCollected { id, pickup } => {
+                info!("Player {id} collected {pickup:?}");
+                if let Pickup::Health(amount) = pickup
+                    && let Some(player_info) = lobby.players.get(&id)
+                    && let Ok(mut player_health) = health_query.get_mut(player_info.client_entity)
+                {
+                    player_health.heal(amount);
+                }
+            }
+            ServerMessages::Explosion { owner, translation, radius } => {
+                info!("Explosion from {owner} at {translation:?}");
+                spawn_explosion_fx(&mut commands, &mut meshes, &mut materials, translation.into(), radius);
+            }
+            ServerMessages::PlayerDied { victim, killer, weapon, assists, respawn_in_secs } => {
+                match killer {
+                    Some(killer) => info!("Player {victim} killed by {killer} with {weapon:?}, assisted by {assists:?}"),
+                    None => info!("Player {victim} died"),
+                }
+                kill_feed.push(victim, killer, weapon, assists);
+                if let Some(player_info) = lobby.players.get(&victim) {
+                    commands.entity(player_info.client_entity)
+                        .insert((Dead::new(respawn_in_secs), Visibility::Hidden))
+                        .remove::<Invulnerable>();
+                }
+            }
+            ServerMessages::PlayerRespawned { id, translation, invulnerable_for_secs } => {
+                info!("Player {id} respawned at {translation:?}");
+                if let Some(player_info) = lobby.players.get(&id) {
+                    if let Ok(mut player_health) = health_query.get_mut(player_info.client_entity) {
+                        player_health.current = player_health.max;
+                    }
+
+                    let mut player_commands = commands.entity(player_info.client_entity);
+                    player_commands
+                        .remove::<Dead>()
+                        .insert((
+                            Transform::from_translation(translation.into()),
+                            Visibility::Inherited,
+                            Invulnerable::new(invulnerable_for_secs),
+                        ));
+                    if id != client_id {
+                        player_commands.insert(SnapshotBuffer::default());
+                    }
+                }
+            }
         }
     }
 }
 
+pub fn receive_snapshots(
+    mut client: ResMut<RenetClient>,
+    mut server_clock: ResMut<ServerClock>,
+    mut snapshot_history: ResMut<SnapshotHistory>,
+    mut received_snapshots: ResMut<ReceivedSnapshots>,
+    time: Res<Time>,
+) {
+    while let Some(message) = client.receive_message(ServerChannel::NetworkedEntities) {
+        let snapshot_delta: SnapshotDelta = match bincode::deserialize(&message) {
+            Ok(delta) => delta,
+            Err(e) => {
+                error!("Erreur de désérialisation du snapshot: {:?}", e);
+                continue;
+            }
+        };
+
+        let tick = snapshot_delta.tick;
+        if !server_clock.accept_snapshot(tick) {
+            continue;
+        }
+        server_clock.observe(tick, time.elapsed_secs_f64());
+
+        let baseline = snapshot_delta.baseline_tick.and_then(|baseline_tick| snapshot_history.get(baseline_tick));
+        let networked_entities = match decode_snapshot(snapshot_delta, baseline) {
+            Ok(networked_entities) => networked_entities,
+            Err(e) => {
+                error!("Snapshot {tick} ignoré: {:?}", e);
+                continue;
+            }
+        };
+
+        match bincode::serialize(&SnapshotAck { tick }) {
+            Ok(ack_message) => client.send_message(ClientChannel::SnapshotAck, ack_message),
+            Err(e) => error!("Erreur de sérialisation de SnapshotAck: {:?}", e),
+        }
+
+        snapshot_history.push(networked_entities.clone());
+        received_snapshots.0.push(networked_entities);
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn client_create_player_entity(
     client_id: u64,
@@ -86,21 +252,22 @@ pub fn client_create_player_entity(
     commands: &mut Commands,
     texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
     player_textures: &mut Res<PlayerTextures>,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<ColorMaterial>>,
     weapon_textures: &mut Res<WeaponTextures>,
 ) -> Entity {
     let position = translation.into();
     let rick_texture = PlayerTextureEntity::new(&player_texture_type);
+    let weapon_texture = WeaponTextureEntity::new(&weapon_texture_type);
 
-    let (pivot, weapon) = spawn_weapon_entity(
-        commands,
-        meshes,
-        materials,
+    let (pivot, weapon) = spawn_weapon_entity(commands, &weapon_texture_type);
+    commands.entity(weapon).insert(weapon_sprite(
+        &weapon_texture_type,
+        SpriteFrame {
+            index: weapon_texture.animation_indices.first as usize,
+            ..Default::default()
+        },
         texture_atlas_layouts,
         weapon_textures,
-        &weapon_texture_type,
-    );
+    ));
 
     let player_args = SpawnPlayerParams {
         pivot,
@@ -111,12 +278,17 @@ pub fn client_create_player_entity(
         client_id,
     };
 
-    let player = spawn_player_entity(
-        commands,
+    let player = spawn_player_entity(commands, player_args);
+    commands.entity(player).insert(PlayerWeaponSelected { weapon_texture_type });
+    commands.entity(player).insert(player_sprite(
+        &rick_texture.player_texture_type,
+        SpriteFrame {
+            index: rick_texture.animation_indices.first as usize,
+            ..Default::default()
+        },
         texture_atlas_layouts,
         player_textures,
-        player_args,
-    );
+    ));
 
     commands.entity(player).add_child(pivot);
     commands.entity(pivot).add_child(weapon);
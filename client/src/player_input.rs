@@ -1,7 +1,9 @@
+use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
 use bevy_renet2::prelude::RenetClient;
-use game_core::network::network::ClientChannel;
-use game_core::player::component::{ControlledPlayer, MouseWorldCoords, PlayerInput};
+use game_core::game_mode::component::Team;
+use game_core::network::network::{ClientChannel, PlayerCommand};
+use game_core::player::component::{ControlledPlayer, Dead, MouseWorldCoords, PlayerInput};
 
 const UP: [KeyCode; 2] = [KeyCode::KeyW, KeyCode::ArrowUp];
 const DOWN: [KeyCode; 2] = [KeyCode::KeyS, KeyCode::ArrowDown];
@@ -9,6 +11,9 @@ const LEFT: [KeyCode; 2] = [KeyCode::KeyA, KeyCode::ArrowLeft];
 const RIGHT: [KeyCode; 2] = [KeyCode::KeyD, KeyCode::ArrowRight];
 const JUMP: KeyCode = KeyCode::Space;
 const SHOOT: MouseButton = MouseButton::Left;
+const RELOAD: KeyCode = KeyCode::KeyR;
+const WEAPON_SLOTS: [KeyCode; 4] = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4];
+const CHANGE_TEAM: KeyCode = KeyCode::KeyT;
 
 
 pub fn send_input(
@@ -16,13 +21,17 @@ pub fn send_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
     mut client: ResMut<RenetClient>,
+    dead_query: Query<(), (With<ControlledPlayer>, With<Dead>)>,
 ) {
-    player_input.up = keyboard_input.any_pressed(UP);
-    player_input.down = keyboard_input.any_pressed(DOWN);
-    player_input.left = keyboard_input.any_pressed(LEFT);
-    player_input.right = keyboard_input.any_pressed(RIGHT);
-    player_input.jump = keyboard_input.just_pressed(JUMP);
-    player_input.shoot = mouse_input.just_pressed(SHOOT);
+    let alive = dead_query.is_empty();
+    player_input.up = alive && keyboard_input.any_pressed(UP);
+    player_input.down = alive && keyboard_input.any_pressed(DOWN);
+    player_input.left = alive && keyboard_input.any_pressed(LEFT);
+    player_input.right = alive && keyboard_input.any_pressed(RIGHT);
+    player_input.jump = alive && keyboard_input.just_pressed(JUMP);
+    player_input.shoot = alive && mouse_input.pressed(SHOOT);
+    player_input.reload = alive && keyboard_input.just_pressed(RELOAD);
+    player_input.sequence = player_input.sequence.wrapping_add(1);
 
     let input_message = match bincode::serialize(&*player_input) {
         Ok(msg) => msg,
@@ -35,6 +44,47 @@ pub fn send_input(
 }
 
 
+pub fn send_weapon_commands(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut client: ResMut<RenetClient>,
+) {
+    let mut commands: Vec<PlayerCommand> = WEAPON_SLOTS.iter()
+        .enumerate()
+        .filter(|(_, key)| keyboard_input.just_pressed(**key))
+        .map(|(slot, _)| PlayerCommand::SelectWeapon { slot: slot as u8 })
+        .collect();
+
+    let scroll: f32 = mouse_wheel.read().map(|wheel| wheel.y).sum();
+    if scroll != 0.0 {
+        commands.push(PlayerCommand::CycleWeapon { offset: if scroll > 0.0 { -1 } else { 1 } });
+    }
+
+    for command in commands {
+        match bincode::serialize(&command) {
+            Ok(message) => client.send_message(ClientChannel::Command, message),
+            Err(e) => error!("Erreur de sérialisation de PlayerCommand: {:?}", e),
+        }
+    }
+}
+
+/// Asks to join the other team; the server refuses when it would unbalance the teams.
+pub fn send_team_command(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut client: ResMut<RenetClient>,
+    team_query: Query<&Team, With<ControlledPlayer>>,
+) {
+    if !keyboard_input.just_pressed(CHANGE_TEAM) {
+        return;
+    }
+    let Ok(team) = team_query.single() else { return };
+
+    match bincode::serialize(&PlayerCommand::ChangeTeam { team: team.other() }) {
+        Ok(message) => client.send_message(ClientChannel::Command, message),
+        Err(e) => error!("Erreur de sérialisation de PlayerCommand: {:?}", e),
+    }
+}
+
 #[derive(Component)]
 pub struct MainCamera;
 
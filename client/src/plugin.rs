@@ -1,87 +1,107 @@
-use crate::animation::player_animation;
-use crate::network::system::client_event;
-use crate::network::{ClientLobby, Connected, PlayerMapping, ProjectileMapping};
-use crate::player_input::{send_input, update_mouse_coords, MainCamera};
+use crate::animation::{animate_explosion_fx, flag_animation, monster_animation, pickup_animation, player_animation, tint_team_players, update_weapon_sprites};
+use crate::config::ClientSettings;
+use crate::hud::{announcement_hud, kill_feed_hud, match_hud, scoreboard_hud, wave_hud, Announcement, KillFeed, MatchHud, ScoreboardHud, WaveHud};
+use crate::interpolation::{interpolate_snapshots, InterpolationConfig};
+use crate::level::spawn_level;
+use crate::network::clock::ServerClock;
+use crate::network::connection::{detect_disconnection, disconnected_window, new_client_connection, Disconnected};
+use crate::network::system::{client_event, receive_snapshots};
+use crate::network::{ClientLobby, Connected, FlagMapping, MonsterMapping, PelletMapping, PickupMapping, PlayerMapping, ProjectileMapping, ReceivedSnapshots};
+use crate::player_input::{send_input, send_team_command, send_weapon_commands, update_mouse_coords, MainCamera};
+use crate::prediction::{predict_controlled_player, reconcile_controlled_player, InputHistory, PendingReconciliation};
 use bevy::app::{App, Plugin, Startup, Update};
 use bevy::log::error;
-use bevy::prelude::{Camera2d, Commands, EventReader, IntoScheduleConfigs};
-use bevy_renet2::netcode::{ClientAuthentication, NetcodeClientPlugin, NetcodeClientTransport, NetcodeTransportError};
-use bevy_renet2::prelude::{client_connected, RenetClient};
-use game_core::decor::system::setup_ground;
-use game_core::network::network::{connection_config, PROTOCOL_ID};
-use game_core::network::utils::{get_current_time, get_native_socket, get_socket};
-use game_core::player::component::{AimDirection, CurrentClientId, MouseWorldCoords, PlayerInput};
-use game_core::texture::system::{load_player_textures, load_weapon_fx_textures, load_weapon_textures};
+use bevy::prelude::{resource_exists, AssetApp, Camera2d, Commands, IntoScheduleConfigs};
+use bevy_egui::EguiPrimaryContextPass;
+use bevy_renet2::netcode::NetcodeClientPlugin;
+use bevy_renet2::prelude::client_connected;
+use game_core::decor::level::{LevelDefinition, LevelLoader};
+use game_core::decor::tiled::TiledLoader;
+use game_core::network::snapshot::SnapshotHistory;
+use game_core::player::component::{AimDirection, MouseWorldCoords, PlayerInput};
+use game_core::player::system::expire_invulnerability;
+use game_core::texture::system::{load_monster_textures, load_player_textures, load_weapon_fx_textures, load_weapon_textures};
 
-pub struct ClientPlugin;
+pub struct ClientPlugin {
+    pub settings: ClientSettings,
+}
 
 impl Plugin for ClientPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ClientLobby::default());
         app.insert_resource(PlayerMapping::default());
         app.insert_resource(ProjectileMapping::default());
+        app.insert_resource(PelletMapping::default());
+        app.insert_resource(PickupMapping::default());
+        app.insert_resource(FlagMapping::default());
+        app.insert_resource(KillFeed::default());
+        app.insert_resource(MonsterMapping::default());
         app.insert_resource(PlayerInput::default());
         app.insert_resource(MouseWorldCoords::default());
         app.insert_resource(AimDirection::default());
+        app.insert_resource(InputHistory::default());
+        app.insert_resource(PendingReconciliation::default());
+        app.insert_resource(InterpolationConfig { delay_ticks: self.settings.interpolation_delay_ticks });
+        app.insert_resource(ServerClock::new(self.settings.tick_rate));
+        app.insert_resource(ReceivedSnapshots::default());
+        app.insert_resource(SnapshotHistory::default());
+        app.init_asset::<LevelDefinition>();
+        app.init_asset_loader::<LevelLoader>();
+        app.init_asset_loader::<TiledLoader>();
 
-        add_netcode_network(app);
+        add_netcode_network(app, self.settings.clone());
 
         app.add_systems(Update, (
             client_event,
             send_input,
+            send_weapon_commands,
+            send_team_command,
             update_mouse_coords,
+            receive_snapshots.before(player_animation),
+            pickup_animation.after(receive_snapshots).before(player_animation),
+            monster_animation.after(receive_snapshots).before(player_animation),
+            flag_animation.after(receive_snapshots).before(player_animation),
             player_animation,
+            predict_controlled_player.after(send_input),
+            reconcile_controlled_player.after(player_animation),
+            interpolate_snapshots.after(player_animation),
+            expire_invulnerability,
+            animate_explosion_fx,
+            update_weapon_sprites.after(player_animation),
+            tint_team_players.after(player_animation),
+            spawn_level.after(client_event),
         ).in_set(Connected));
 
         app.add_systems(Startup, (
             setup_camera,
-            setup_ground,
             load_player_textures,
             load_weapon_textures,
             load_weapon_fx_textures,
+            load_monster_textures,
         ));
     }
 }
-fn add_netcode_network(app: &mut App) {
+fn add_netcode_network(app: &mut App, settings: ClientSettings) {
     app.add_plugins(NetcodeClientPlugin);
     app.configure_sets(Update, Connected.run_if(client_connected));
 
-    let client = RenetClient::new(connection_config(), false);
-
-    let server_addr = "127.0.0.1:5000".parse().expect("Échec du parsing de l'adresse publique");
-    let socket_addr = "127.0.0.1:0".parse().expect("Échec du parsing de l'adresse du socket");
-    let socket = get_socket(socket_addr);
-
-    let current_time: std::time::Duration = get_current_time();
-
-    let client_id = current_time.as_millis() as u64;
-    let authentication = ClientAuthentication::Unsecure {
-        client_id,
-        protocol_id: PROTOCOL_ID,
-        socket_id: 0,
-        server_addr,
-        user_data: None,
-    };
-
-    let native_socket = get_native_socket(socket);
-
-    let transport = NetcodeClientTransport::new(current_time, authentication, native_socket).unwrap_or_else(|e| {
+    let (client, transport, client_id) = new_client_connection(&settings).unwrap_or_else(|e| {
         error!("Échec de la création du transport client");
         panic!("reason : {e}");
     });
 
     app.insert_resource(client);
     app.insert_resource(transport);
-    app.insert_resource(CurrentClientId(client_id));
-
-    #[allow(clippy::never_loop)]
-    fn panic_on_error_system(mut renet_error: EventReader<NetcodeTransportError>) {
-        for e in renet_error.read() {
-            panic!("{}", e);
-        }
-    }
+    app.insert_resource(client_id);
+    app.insert_resource(settings);
 
-    app.add_systems(Update, panic_on_error_system);
+    app.add_systems(Update, detect_disconnection);
+    app.add_systems(EguiPrimaryContextPass, disconnected_window.run_if(resource_exists::<Disconnected>));
+    app.add_systems(EguiPrimaryContextPass, wave_hud.run_if(resource_exists::<WaveHud>));
+    app.add_systems(EguiPrimaryContextPass, match_hud.run_if(resource_exists::<MatchHud>));
+    app.add_systems(EguiPrimaryContextPass, announcement_hud.run_if(resource_exists::<Announcement>));
+    app.add_systems(EguiPrimaryContextPass, scoreboard_hud.run_if(resource_exists::<ScoreboardHud>));
+    app.add_systems(EguiPrimaryContextPass, kill_feed_hud);
 }
 
 fn setup_camera(mut commands: Commands) {
@@ -0,0 +1,77 @@
+use bevy::prelude::{Has, Query, Res, ResMut, Resource, Time, Transform, With, Without};
+use bevy_rapier2d::dynamics::Velocity;
+use game_core::game_mode::flag::{slow_flag_carrier, FlagCarrier};
+use game_core::network::network::NetworkedPlayer;
+use game_core::player::component::{ControlledPlayer, Dead, Grounded, JumpCounter, Player, PlayerInput};
+use game_core::player::math::{integrate_translation, step_player_movement};
+use std::collections::VecDeque;
+
+const MAX_PENDING_INPUTS: usize = 256;
+
+pub struct PendingInput {
+    pub input: PlayerInput,
+    pub delta_secs: f32,
+}
+
+/// Inputs already applied locally but not yet acknowledged by the server.
+#[derive(Resource, Default)]
+pub struct InputHistory(pub VecDeque<PendingInput>);
+
+/// Latest authoritative state of the controlled player, applied by `reconcile_controlled_player`.
+#[derive(Resource, Default)]
+pub struct PendingReconciliation(pub Option<NetworkedPlayer>);
+
+/// Steps the controlled player once per rendered frame with the input sent that frame.
+/// The pseudo-gravity of `step_player_movement` is thus applied per client frame, while the server
+/// applies it per server frame and lets Rapier integrate; at different frame rates a predicted jump
+/// drifts from the server's and `reconcile_controlled_player` pulls it back on the next snapshot.
+#[allow(clippy::type_complexity)]
+pub fn predict_controlled_player(
+    time: Res<Time>,
+    player_input: Res<PlayerInput>,
+    mut history: ResMut<InputHistory>,
+    mut query: Query<(&Player, &mut Transform, &mut Velocity, &mut JumpCounter, &Grounded, Has<FlagCarrier>), (With<ControlledPlayer>, Without<Dead>)>,
+) {
+    let Ok((player, mut transform, mut velocity, mut jump_counter, grounded, flag_carrier)) = query.single_mut() else { return };
+    let delta_secs = time.delta_secs();
+
+    step_player_movement(player, &player_input, &mut velocity, &mut jump_counter, grounded);
+    if flag_carrier {
+        slow_flag_carrier(&mut velocity);
+    }
+    integrate_translation(&mut transform.translation, &velocity, delta_secs);
+
+    history.0.push_back(PendingInput { input: *player_input, delta_secs });
+    if history.0.len() > MAX_PENDING_INPUTS {
+        history.0.pop_front();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn reconcile_controlled_player(
+    mut reconciliation: ResMut<PendingReconciliation>,
+    mut history: ResMut<InputHistory>,
+    mut query: Query<(&Player, &mut Transform, &mut Velocity, &mut JumpCounter, &mut Grounded, Has<FlagCarrier>), With<ControlledPlayer>>,
+) {
+    let Some(server_state) = reconciliation.0.take() else { return };
+    let Ok((player, mut transform, mut velocity, mut jump_counter, mut grounded, flag_carrier)) = query.single_mut() else { return };
+
+    history.0.retain(|pending| is_sequence_newer(pending.input.sequence, server_state.last_input_sequence));
+
+    transform.translation = server_state.translation.into();
+    velocity.linvel = server_state.velocity.into();
+    grounded.0 = server_state.grounded;
+    jump_counter.jumps_left = server_state.jumps_left;
+
+    for pending in history.0.iter() {
+        step_player_movement(player, &pending.input, &mut velocity, &mut jump_counter, &grounded);
+        if flag_carrier {
+            slow_flag_carrier(&mut velocity);
+        }
+        integrate_translation(&mut transform.translation, &velocity, pending.delta_secs);
+    }
+}
+
+fn is_sequence_newer(sequence: u32, acknowledged: u32) -> bool {
+    (sequence.wrapping_sub(acknowledged) as i32) > 0
+}
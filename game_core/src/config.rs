@@ -0,0 +1,62 @@
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Command-line arguments of the form `--name value` or `--flag`.
+#[derive(Debug, Clone, Default)]
+pub struct CliArgs(Vec<String>);
+
+impl CliArgs {
+    pub fn from_env() -> Self {
+        Self(std::env::args().skip(1).collect())
+    }
+
+    pub fn new(args: Vec<String>) -> Self {
+        Self(args)
+    }
+
+    /// First argument when it is not an option, e.g. `issue-token`.
+    pub fn subcommand(&self) -> Option<&str> {
+        self.0.first().map(String::as_str).filter(|arg| !arg.starts_with("--"))
+    }
+
+    /// Arguments following the subcommand.
+    pub fn positionals(&self) -> &[String] {
+        let end = self.0.iter().position(|arg| arg.starts_with("--")).unwrap_or(self.0.len());
+        self.0.get(1..end).unwrap_or_default()
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.0.iter().any(|arg| arg == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.0.iter()
+            .position(|arg| arg == name)
+            .and_then(|index| self.0.get(index + 1))
+            .map(String::as_str)
+    }
+
+    pub fn parse<T: FromStr>(&self, name: &str) -> Result<Option<T>, String>
+    where
+        T::Err: std::fmt::Display,
+    {
+        self.value(name)
+            .map(|value| value.parse().map_err(|e| format!("valeur invalide pour {name} ({value}): {e}")))
+            .transpose()
+    }
+}
+
+/// Loads a RON config file; a missing `default_path` falls back to `T::default()`,
+/// a missing explicit path is an error.
+pub fn load_ron_config<T: DeserializeOwned + Default>(path: Option<&str>, default_path: &str) -> Result<T, String> {
+    let path = match path {
+        Some(path) => Path::new(path),
+        None if Path::new(default_path).exists() => Path::new(default_path),
+        None => return Ok(T::default()),
+    };
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("impossible de lire {}: {e}", path.display()))?;
+    ron::from_str(&content).map_err(|e| format!("configuration {} invalide: {e}", path.display()))
+}
@@ -1,6 +1,8 @@
 use bevy::math::Vec2;
-use bevy::prelude::Component;
+use bevy::prelude::{Component, Resource};
+use serde::Deserialize;
 
+/// Solid level geometry; one-way platforms also carry a `OneWayPlatform`.
 #[derive(Component)]
 pub struct Ground {
     pub size: Vec2,
@@ -11,4 +13,50 @@ impl Ground {
     pub fn new(size: Vec2, position: Vec2) -> Self {
         Self { size, position }
     }
-}
\ No newline at end of file
+}
+
+/// Platform players jump through from below and stand on from above.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OneWayPlatform;
+
+/// Level marker where players (re)spawn.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SpawnPoint;
+
+/// Everything spawned from the current level, despawned when another one is loaded.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LevelEntity;
+
+/// Axis-aligned rectangle of the level.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Area {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Area {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn contains(&self, position: Vec2) -> bool {
+        position.cmpge(self.min).all() && position.cmple(self.max).all()
+    }
+}
+
+/// Playable area of the level and the kill zones inside it; a player leaving the first or entering one of the others dies.
+#[derive(Resource, Debug, Clone)]
+pub struct KillVolume {
+    pub bounds: Area,
+    pub kill_zones: Vec<Area>,
+}
+
+impl KillVolume {
+    pub fn new(bounds: Area, kill_zones: Vec<Area>) -> Self {
+        Self { bounds, kill_zones }
+    }
+
+    pub fn is_safe(&self, position: Vec2) -> bool {
+        self.bounds.contains(position) && !self.kill_zones.iter().any(|zone| zone.contains(position))
+    }
+}
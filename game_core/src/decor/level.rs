@@ -0,0 +1,256 @@
+use crate::decor::component::Area;
+use crate::decor::tiled::TILED_EXTENSIONS;
+use crate::game_mode::flag::FlagBase;
+use crate::monster::component::MonsterSpawn;
+use crate::pickup::component::PickupSpawn;
+use bevy::asset::io::Reader;
+use bevy::asset::{Asset, AssetLoader, Handle, LoadContext};
+use bevy::math::{UVec2, Vec2};
+use bevy::prelude::Resource;
+use bevy::reflect::TypePath;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+pub const DEFAULT_LEVEL: &str = "default";
+const LEVEL_EXTENSION: &str = "level.ron";
+
+/// Asset path of a level, relative to the asset folder; Tiled maps are named with their extension.
+pub fn level_path(name: &str) -> String {
+    if is_tiled_map(name) {
+        format!("levels/{name}")
+    } else {
+        format!("levels/{name}.{LEVEL_EXTENSION}")
+    }
+}
+
+/// Level names are used in asset paths, so they are kept to plain file names.
+pub fn is_valid_level_name(name: &str) -> bool {
+    let stem = name.strip_suffix(".tmx").or_else(|| name.strip_suffix(".tmj")).unwrap_or(name);
+    !stem.is_empty() && stem.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn is_tiled_map(name: &str) -> bool {
+    TILED_EXTENSIONS.iter().any(|extension| name.ends_with(&format!(".{extension}")))
+}
+
+/// Content of a `levels/*.level.ron` file, or of a Tiled map converted by `TiledLoader`.
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct LevelDefinition {
+    pub platforms: Vec<PlatformDefinition>,
+    pub spawn_points: Vec<Vec2>,
+    #[serde(default)]
+    pub pickups: Vec<PickupSpawn>,
+    #[serde(default)]
+    pub monster_spawners: Vec<MonsterSpawn>,
+    /// Only used by the capture-the-flag mode.
+    #[serde(default)]
+    pub flag_bases: Vec<FlagBase>,
+    pub bounds: Area,
+    #[serde(default)]
+    pub kill_zones: Vec<Area>,
+    #[serde(default)]
+    pub backgrounds: Vec<BackgroundLayer>,
+    /// Tile images referenced by `tile_layers`.
+    #[serde(default)]
+    pub tile_sheets: Vec<TileSheet>,
+    #[serde(default)]
+    pub tile_layers: Vec<TileLayer>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlatformDefinition {
+    pub position: Vec2,
+    pub size: Vec2,
+    #[serde(default)]
+    pub one_way: bool,
+    /// Drawn by clients; platforms generated from tiles are already drawn by them.
+    #[serde(default = "visible")]
+    pub visible: bool,
+}
+
+fn visible() -> bool {
+    true
+}
+
+/// Decoration drawn behind the level by clients, an image or a plain color.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackgroundLayer {
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Negative, layers further from zero are drawn behind the others.
+    pub depth: f32,
+    #[serde(default = "white")]
+    pub color: [f32; 3],
+    /// Asset path, tinted by `color`.
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+fn white() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+/// Image cut into a grid of tiles, in pixels.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileSheet {
+    pub image: String,
+    pub tile_size: UVec2,
+    pub columns: u32,
+    pub rows: u32,
+    #[serde(default)]
+    pub spacing: u32,
+    #[serde(default)]
+    pub margin: u32,
+}
+
+/// Tiles of one sheet drawn at the same depth.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileLayer {
+    /// Index in `LevelDefinition::tile_sheets`.
+    pub sheet: usize,
+    pub depth: f32,
+    pub tiles: Vec<TileDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileDefinition {
+    /// Center of the tile.
+    pub position: Vec2,
+    /// Index of the tile in its sheet, row by row.
+    pub index: u32,
+    #[serde(default)]
+    pub flip_x: bool,
+    #[serde(default)]
+    pub flip_y: bool,
+}
+
+impl LevelDefinition {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let positive_size = |size: Vec2| size.is_finite() && size.cmpgt(Vec2::ZERO).all();
+        if self.platforms.is_empty() || self.spawn_points.is_empty() {
+            return Err("au moins une plateforme et un point d'apparition sont requis".to_string());
+        }
+        if let Some(platform) = self.platforms.iter().find(|platform| !platform.position.is_finite() || !positive_size(platform.size)) {
+            return Err(format!("plateforme invalide: {platform:?}"));
+        }
+        if let Some(layer) = self.backgrounds.iter().find(|layer| !positive_size(layer.size) || layer.depth >= 0.0) {
+            return Err(format!("arrière-plan invalide: {layer:?}"));
+        }
+        if let Some(layer) = self.tile_layers.iter().find(|layer| layer.depth >= 0.0) {
+            return Err(format!("couche de tuiles devant les joueurs: profondeur {}", layer.depth));
+        }
+        for layer in &self.tile_layers {
+            let Some(sheet) = self.tile_sheets.get(layer.sheet) else {
+                return Err(format!("feuille de tuiles inconnue: {}", layer.sheet));
+            };
+            if let Some(tile) = layer.tiles.iter().find(|tile| tile.index >= sheet.columns * sheet.rows) {
+                return Err(format!("tuile {} hors de {}", tile.index, sheet.image));
+            }
+        }
+        if !positive_size(self.bounds.max - self.bounds.min) {
+            return Err("bounds doit avoir une taille positive".to_string());
+        }
+        if self.spawn_points.iter().any(|spawn_point| !self.bounds.contains(*spawn_point)) {
+            return Err("tous les points d'apparition doivent être dans bounds".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Level being played and whether its entities were spawned yet.
+#[derive(Resource, Debug)]
+pub struct LevelHandle {
+    pub name: String,
+    pub handle: Handle<LevelDefinition>,
+    pub spawned: bool,
+}
+
+impl LevelHandle {
+    pub fn new(name: String, handle: Handle<LevelDefinition>) -> Self {
+        Self { name, handle, spawned: false }
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Tiled(String),
+    Invalid(String),
+}
+
+impl Display for LevelLoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LevelLoaderError::Io(e) => write!(f, "impossible de lire le niveau: {e}"),
+            LevelLoaderError::Ron(e) => write!(f, "niveau invalide: {e}"),
+            LevelLoaderError::Tiled(e) => write!(f, "carte Tiled invalide: {e}"),
+            LevelLoaderError::Invalid(e) => write!(f, "niveau invalide: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelLoaderError {}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = LevelDefinition;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(LevelLoaderError::Io)?;
+        let level: LevelDefinition = ron::de::from_bytes(&bytes).map_err(LevelLoaderError::Ron)?;
+        level.validate().map_err(LevelLoaderError::Invalid)?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[LEVEL_EXTENSION]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_level() -> LevelDefinition {
+        ron::de::from_bytes(include_bytes!("../../../assets/levels/default.level.ron")).unwrap()
+    }
+
+    #[test]
+    fn default_level_is_valid() {
+        let level = default_level();
+        assert!(level.validate().is_ok());
+        assert_eq!(level.spawn_points.len(), 4);
+    }
+
+    #[test]
+    fn level_without_platforms_is_invalid() {
+        let mut level = default_level();
+        level.platforms.clear();
+        assert!(level.validate().is_err());
+    }
+
+    #[test]
+    fn spawn_point_outside_bounds_is_invalid() {
+        let mut level = default_level();
+        level.spawn_points.push(level.bounds.max + Vec2::ONE);
+        assert!(level.validate().is_err());
+    }
+
+    #[test]
+    fn background_in_front_of_players_is_invalid() {
+        let mut level = default_level();
+        level.backgrounds[0].depth = 0.0;
+        assert!(level.validate().is_err());
+    }
+}
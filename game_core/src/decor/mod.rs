@@ -1,2 +1,5 @@
 pub mod system;
-pub mod component;
\ No newline at end of file
+pub mod component;
+pub mod level;
+pub mod platform;
+pub mod tiled;
@@ -0,0 +1,26 @@
+use crate::decor::component::OneWayPlatform;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Query, With};
+use bevy_rapier2d::pipeline::{BevyPhysicsHooks, ContactModificationContextView};
+use bevy_rapier2d::rapier::math::Vector;
+use std::f32::consts::FRAC_PI_4;
+
+/// Physics hooks of the server simulation: contacts with a `OneWayPlatform` only hold from above.
+#[derive(SystemParam)]
+pub struct OneWayPlatformHooks<'w, 's> {
+    platforms: Query<'w, 's, (), With<OneWayPlatform>>,
+}
+
+impl BevyPhysicsHooks for OneWayPlatformHooks<'_, '_> {
+    fn modify_solver_contacts(&self, context: ContactModificationContextView) {
+        // The allowed normal points out of the first collider of the pair.
+        let allowed_normal = if self.platforms.contains(context.collider1()) {
+            Vector::y()
+        } else if self.platforms.contains(context.collider2()) {
+            -Vector::y()
+        } else {
+            return;
+        };
+        context.raw.update_as_oneway_platform(&allowed_normal, FRAC_PI_4);
+    }
+}
@@ -1,32 +1,142 @@
-use crate::decor::component::Ground;
-use bevy::prelude::{Assets, Color, ColorMaterial, Commands, GlobalTransform, Mesh, Mesh2d, MeshMaterial2d, Rectangle, ResMut, Transform, Vec2};
-use bevy_rapier2d::prelude::{Collider, RigidBody};
-
-pub fn setup_ground(
-    commands: Commands,
-    meshes: ResMut<Assets<Mesh>>,
-    materials: ResMut<Assets<ColorMaterial>>,
-) {
-    let ground = Ground::new(
-        Vec2::new(800.0, 25.0),
-        Vec2::new(0.0, -200.0),
-    );
-    create_ground(commands, meshes, materials, ground);
-}
-
-pub fn create_ground(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    ground: Ground,
+use crate::decor::component::{Area, Ground, KillVolume, LevelEntity, OneWayPlatform, SpawnPoint};
+use crate::decor::level::{level_path, LevelDefinition, LevelHandle, PlatformDefinition};
+use crate::game_mode::flag::spawn_flag;
+use crate::monster::component::MonsterSpawner;
+use crate::pickup::command::spawn_pickup;
+use bevy::asset::AssetServer;
+use bevy::image::{TextureAtlas, TextureAtlasLayout};
+use bevy::prelude::{Assets, Color, ColorMaterial, Commands, Entity, GlobalTransform, Mesh, Mesh2d, MeshMaterial2d, Rectangle, ResMut, Sprite, Transform, UVec2, Vec2};
+use bevy_rapier2d::prelude::{ActiveHooks, Collider, RigidBody};
+
+/// Playable area until a level is loaded.
+pub fn default_kill_volume() -> KillVolume {
+    KillVolume::new(Area::new(Vec2::new(-1200.0, -1000.0), Vec2::new(1200.0, 2000.0)), Vec::new())
+}
+
+pub fn load_level(asset_server: &AssetServer, name: &str) -> LevelHandle {
+    LevelHandle::new(name.to_string(), asset_server.load(level_path(name)))
+}
+
+/// Platform colliders, spawn points and kill volume, for the server simulation.
+pub fn spawn_level_colliders(commands: &mut Commands, level: &LevelDefinition) {
+    for platform in &level.platforms {
+        spawn_platform_collider(commands, platform);
+    }
+
+    for position in &level.spawn_points {
+        commands.spawn((
+            SpawnPoint,
+            LevelEntity,
+            Transform::from_translation(position.extend(0.0)),
+        ));
+    }
+
+    commands.insert_resource(KillVolume::new(level.bounds, level.kill_zones.clone()));
+}
+
+/// Pickups with their colliders, for the server simulation; clients draw them from snapshots.
+pub fn spawn_level_pickups(commands: &mut Commands, level: &LevelDefinition) {
+    for pickup_spawn in &level.pickups {
+        let pickup = spawn_pickup(commands, pickup_spawn.clone());
+        commands.entity(pickup).insert(LevelEntity);
+    }
+}
+
+/// Spawners only; the server fills them with monsters.
+pub fn spawn_level_monster_spawners(commands: &mut Commands, level: &LevelDefinition) {
+    for monster_spawn in &level.monster_spawners {
+        commands.spawn((
+            MonsterSpawner::new(monster_spawn.monster_type, monster_spawn.respawn_secs),
+            LevelEntity,
+            Transform::from_translation(monster_spawn.position.extend(0.0)),
+        ));
+    }
+}
+
+/// Flags at their bases, for the server simulation of the capture-the-flag mode.
+pub fn spawn_level_flags(commands: &mut Commands, level: &LevelDefinition) {
+    for flag_base in &level.flag_bases {
+        let flag = spawn_flag(commands, flag_base.clone());
+        commands.entity(flag).insert(LevelEntity);
+    }
+}
+
+/// Platforms, background and tile layers, drawn by clients.
+pub fn spawn_level_visuals(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
+    asset_server: &AssetServer,
+    level: &LevelDefinition,
 ) {
-    commands.spawn((
-        Mesh2d(meshes.add(Rectangle::new(ground.size.x, ground.size.y))),
-        MeshMaterial2d(materials.add(ColorMaterial::from(Color::srgb(0.3, 0.5, 0.3)))),
-        Transform::from_translation(Vec2::new(ground.position.x, ground.position.y).extend(0.0)),
+    for platform in level.platforms.iter().filter(|platform| platform.visible) {
+        let color = if platform.one_way { Color::srgb(0.45, 0.35, 0.25) } else { Color::srgb(0.3, 0.5, 0.3) };
+        commands.spawn((
+            LevelEntity,
+            Mesh2d(meshes.add(Rectangle::new(platform.size.x, platform.size.y))),
+            MeshMaterial2d(materials.add(ColorMaterial::from(color))),
+            Transform::from_translation(platform.position.extend(0.0)),
+        ));
+    }
+
+    for layer in &level.backgrounds {
+        let [red, green, blue] = layer.color;
+        let color = Color::srgb(red, green, blue);
+        let sprite = match &layer.image {
+            Some(image) => Sprite {
+                image: asset_server.load(image.clone()),
+                color,
+                custom_size: Some(layer.size),
+                ..Default::default()
+            },
+            None => Sprite::from_color(color, layer.size),
+        };
+        commands.spawn((
+            LevelEntity,
+            sprite,
+            Transform::from_translation(layer.position.extend(layer.depth)),
+        ));
+    }
+
+    let sheets: Vec<_> = level.tile_sheets.iter()
+        .map(|sheet| {
+            let layout = TextureAtlasLayout::from_grid(
+                sheet.tile_size,
+                sheet.columns,
+                sheet.rows,
+                Some(UVec2::splat(sheet.spacing)),
+                Some(UVec2::splat(sheet.margin)),
+            );
+            (asset_server.load(sheet.image.clone()), texture_atlas_layouts.add(layout))
+        })
+        .collect();
+    for layer in &level.tile_layers {
+        let (image, layout) = &sheets[layer.sheet];
+        for tile in &layer.tiles {
+            let mut sprite = Sprite::from_atlas_image(image.clone(), TextureAtlas { layout: layout.clone(), index: tile.index as usize });
+            sprite.flip_x = tile.flip_x;
+            sprite.flip_y = tile.flip_y;
+            commands.spawn((
+                LevelEntity,
+                sprite,
+                Transform::from_translation(tile.position.extend(layer.depth)),
+            ));
+        }
+    }
+}
+
+fn spawn_platform_collider(commands: &mut Commands, platform: &PlatformDefinition) -> Entity {
+    let mut platform_commands = commands.spawn((
+        Transform::from_translation(platform.position.extend(0.0)),
         RigidBody::Fixed,
-        Collider::cuboid(ground.size.x / 2.0, ground.size.y / 2.0),
+        Collider::cuboid(platform.size.x / 2.0, platform.size.y / 2.0),
         GlobalTransform::default(),
-        ground,
+        Ground::new(platform.size, platform.position),
+        LevelEntity,
     ));
-}
\ No newline at end of file
+    if platform.one_way {
+        platform_commands.insert((OneWayPlatform, ActiveHooks::MODIFY_SOLVER_CONTACTS));
+    }
+    platform_commands.id()
+}
@@ -0,0 +1,804 @@
+use crate::decor::component::Area;
+use crate::decor::level::{LevelDefinition, LevelLoaderError, PlatformDefinition, TileDefinition, TileLayer, TileSheet};
+use crate::game_mode::flag::FlagBase;
+use crate::monster::component::MonsterSpawn;
+use crate::pickup::component::PickupSpawn;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AssetPath, LoadContext};
+use bevy::math::{URect, UVec2, Vec2};
+use roxmltree::Node;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+pub const TILED_EXTENSIONS: [&str; 2] = ["tmx", "tmj"];
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+/// Flip and rotation bits stored in the high bits of a tile gid.
+const GID_FLAGS: u32 = 0xF000_0000;
+/// Depth of the first tile layer, the next ones are drawn in front of it.
+const TILE_LAYER_DEPTH: f32 = -5.0;
+const TILE_LAYER_DEPTH_STEP: f32 = 0.01;
+
+type Properties = HashMap<String, String>;
+
+/// Map read from a `.tmx` or `.tmj` file; pixels, y down, origin at the top left corner.
+#[derive(Debug)]
+struct TiledMap {
+    width: u32,
+    height: u32,
+    tile_size: Vec2,
+    tilesets: Vec<TiledTileset>,
+    layers: Vec<TiledLayer>,
+}
+
+#[derive(Debug)]
+struct TiledTileset {
+    first_gid: u32,
+    source: TilesetSource,
+}
+
+#[derive(Debug)]
+enum TilesetSource {
+    /// Image path relative to the map.
+    Embedded(TileSheet),
+    /// `.tsx` or `.tsj` file relative to the map.
+    External(String),
+}
+
+#[derive(Debug)]
+struct TiledLayer {
+    visible: bool,
+    properties: Properties,
+    content: LayerContent,
+}
+
+#[derive(Debug)]
+enum LayerContent {
+    Tiles(Vec<u32>),
+    Objects(Vec<TiledObject>),
+}
+
+#[derive(Debug)]
+struct TiledObject {
+    name: String,
+    /// Tiled "class", or "type" before Tiled 1.9.
+    class: String,
+    position: Vec2,
+    size: Vec2,
+    /// Tile objects are anchored at their bottom left corner instead of the top left one.
+    tile: bool,
+    properties: Properties,
+}
+
+impl TiledObject {
+    fn top_left(&self) -> Vec2 {
+        if self.tile { self.position - Vec2::new(0.0, self.size.y) } else { self.position }
+    }
+
+    fn property<T: DeserializeOwned>(&self, name: &str) -> Result<T, String> {
+        let value = self.properties.get(name)
+            .ok_or_else(|| format!("propriété {name} manquante sur l'objet {} \"{}\"", self.class, self.name))?;
+        ron::from_str(value).map_err(|e| format!("propriété {name} invalide sur l'objet {} \"{}\": {e}", self.class, self.name))
+    }
+}
+
+fn bool_property(properties: &Properties, name: &str) -> bool {
+    properties.get(name).is_some_and(|value| value == "true")
+}
+
+impl TiledMap {
+    fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32) * self.tile_size
+    }
+
+    /// Tiled position to world position, y up with the map centered on the origin.
+    fn to_world(&self, position: Vec2) -> Vec2 {
+        let size = self.size();
+        Vec2::new(position.x - size.x / 2.0, size.y / 2.0 - position.y)
+    }
+
+    fn area(&self, top_left: Vec2, size: Vec2) -> Area {
+        Area::new(self.to_world(top_left + Vec2::new(0.0, size.y)), self.to_world(top_left + Vec2::new(size.x, 0.0)))
+    }
+
+    /// `sheets` are the resolved tilesets with their first gid, in the map order.
+    fn into_level(self, sheets: Vec<(u32, TileSheet)>) -> Result<LevelDefinition, String> {
+        let size = self.size();
+        let mut level = LevelDefinition {
+            platforms: Vec::new(),
+            spawn_points: Vec::new(),
+            pickups: Vec::new(),
+            monster_spawners: Vec::new(),
+            flag_bases: Vec::new(),
+            bounds: Area::new(-size / 2.0, size / 2.0),
+            kill_zones: Vec::new(),
+            backgrounds: Vec::new(),
+            tile_sheets: Vec::new(),
+            tile_layers: Vec::new(),
+        };
+
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            match &layer.content {
+                LayerContent::Tiles(gids) => {
+                    if gids.len() != (self.width * self.height) as usize {
+                        return Err(format!("couche de {} tuiles pour une carte de {}x{}", gids.len(), self.width, self.height));
+                    }
+                    if layer.visible {
+                        let depth = TILE_LAYER_DEPTH + layer_index as f32 * TILE_LAYER_DEPTH_STEP;
+                        level.tile_layers.extend(self.tile_layers(gids, &sheets, depth)?);
+                    }
+                    if bool_property(&layer.properties, "collision") {
+                        let one_way = bool_property(&layer.properties, "one_way");
+                        let solid: Vec<bool> = gids.iter().map(|gid| gid & !GID_FLAGS != 0).collect();
+                        level.platforms.extend(merge_cells(&solid, self.width, self.height).into_iter().map(|cells| {
+                            let top_left = cells.min.as_vec2() * self.tile_size;
+                            let cells_size = cells.size().as_vec2() * self.tile_size;
+                            PlatformDefinition {
+                                position: self.to_world(top_left + cells_size / 2.0),
+                                size: cells_size,
+                                one_way,
+                                visible: false,
+                            }
+                        }));
+                    }
+                }
+                LayerContent::Objects(objects) => {
+                    for object in objects {
+                        self.add_object(object, &mut level)?;
+                    }
+                }
+            }
+        }
+
+        level.tile_sheets = sheets.into_iter().map(|(_, sheet)| sheet).collect();
+        Ok(level)
+    }
+
+    /// One layer per tile sheet used by `gids`.
+    fn tile_layers(&self, gids: &[u32], sheets: &[(u32, TileSheet)], depth: f32) -> Result<Vec<TileLayer>, String> {
+        let mut tiles: BTreeMap<usize, Vec<TileDefinition>> = BTreeMap::new();
+        for (cell, raw_gid) in gids.iter().enumerate() {
+            let gid = raw_gid & !GID_FLAGS;
+            if gid == 0 {
+                continue;
+            }
+            let Some(sheet_index) = sheets.iter().rposition(|(first_gid, _)| *first_gid <= gid) else {
+                return Err(format!("tuile {gid} sans tileset"));
+            };
+            let (first_gid, sheet) = &sheets[sheet_index];
+
+            // Tiles larger than the grid overflow upward and to the right, like in Tiled.
+            let cell = UVec2::new(cell as u32 % self.width, cell as u32 / self.width).as_vec2();
+            let tile_size = sheet.tile_size.as_vec2();
+            let position = Vec2::new(
+                cell.x * self.tile_size.x + tile_size.x / 2.0,
+                (cell.y + 1.0) * self.tile_size.y - tile_size.y / 2.0,
+            );
+            tiles.entry(sheet_index).or_default().push(TileDefinition {
+                position: self.to_world(position),
+                index: gid - first_gid,
+                flip_x: raw_gid & FLIPPED_HORIZONTALLY != 0,
+                flip_y: raw_gid & FLIPPED_VERTICALLY != 0,
+            });
+        }
+
+        Ok(tiles.into_iter().map(|(sheet, tiles)| TileLayer { sheet, depth, tiles }).collect())
+    }
+
+    /// Objects of another class are decoration for the designers and ignored.
+    fn add_object(&self, object: &TiledObject, level: &mut LevelDefinition) -> Result<(), String> {
+        let top_left = object.top_left();
+        let position = self.to_world(top_left + object.size / 2.0);
+        match object.class.as_str() {
+            "spawn_point" => level.spawn_points.push(position),
+            "pickup" => level.pickups.push(PickupSpawn {
+                pickup: object.property("pickup")?,
+                position,
+                respawn_secs: object.property("respawn_secs")?,
+            }),
+            "monster_spawner" => level.monster_spawners.push(MonsterSpawn {
+                monster_type: object.property("monster_type")?,
+                position,
+                respawn_secs: object.property("respawn_secs")?,
+            }),
+            "flag_base" => level.flag_bases.push(FlagBase {
+                team: object.property("team")?,
+                position,
+            }),
+            "kill_zone" => level.kill_zones.push(self.area(top_left, object.size)),
+            "bounds" => level.bounds = self.area(top_left, object.size),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Covers the set cells of a grid with few rectangles: each one grows right, then down while whole rows stay free.
+fn merge_cells(solid: &[bool], width: u32, height: u32) -> Vec<URect> {
+    let (width, height) = (width as usize, height as usize);
+    let mut free: Vec<bool> = solid.to_vec();
+    let mut rectangles = Vec::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            if !free[y * width + x] {
+                continue;
+            }
+            let run = (x..width).take_while(|&column| free[y * width + column]).count();
+            let rows = (y..height)
+                .take_while(|&row| (x..x + run).all(|column| free[row * width + column]))
+                .count();
+            for row in y..y + rows {
+                free[row * width + x..row * width + x + run].fill(false);
+            }
+            rectangles.push(URect::new(x as u32, y as u32, (x + run) as u32, (y + rows) as u32));
+        }
+    }
+    rectangles
+}
+
+fn parse_tmx(bytes: &[u8]) -> Result<TiledMap, String> {
+    let text = std::str::from_utf8(bytes).map_err(|e| e.to_string())?;
+    let document = roxmltree::Document::parse(text).map_err(|e| e.to_string())?;
+    let map = document.root_element();
+    if map.tag_name().name() != "map" {
+        return Err("élément <map> attendu".to_string());
+    }
+    check_map_kind(map.attribute("orientation"), map.attribute("infinite") == Some("1"))?;
+
+    let mut tilesets = Vec::new();
+    let mut layers = Vec::new();
+    for child in map.children().filter(Node::is_element) {
+        if child.tag_name().name() == "tileset" {
+            let first_gid = xml_number(child, "firstgid")?;
+            let source = match child.attribute("source") {
+                Some(source) => TilesetSource::External(source.to_string()),
+                None => TilesetSource::Embedded(xml_tile_sheet(child)?),
+            };
+            tilesets.push(TiledTileset { first_gid, source });
+        } else {
+            xml_layers(child, true, &mut layers)?;
+        }
+    }
+
+    Ok(TiledMap {
+        width: xml_number(map, "width")?,
+        height: xml_number(map, "height")?,
+        tile_size: Vec2::new(xml_number(map, "tilewidth")?, xml_number(map, "tileheight")?),
+        tilesets,
+        layers,
+    })
+}
+
+fn parse_tsx(bytes: &[u8]) -> Result<TileSheet, String> {
+    let text = std::str::from_utf8(bytes).map_err(|e| e.to_string())?;
+    let document = roxmltree::Document::parse(text).map_err(|e| e.to_string())?;
+    xml_tile_sheet(document.root_element())
+}
+
+fn xml_number<T: FromStr>(node: Node, name: &str) -> Result<T, String> {
+    node.attribute(name)
+        .ok_or_else(|| format!("attribut {name} manquant sur <{}>", node.tag_name().name()))?
+        .parse()
+        .map_err(|_| format!("attribut {name} invalide sur <{}>", node.tag_name().name()))
+}
+
+fn xml_number_or<T: FromStr>(node: Node, name: &str, default: T) -> Result<T, String> {
+    match node.attribute(name) {
+        Some(_) => xml_number(node, name),
+        None => Ok(default),
+    }
+}
+
+fn xml_tile_sheet(tileset: Node) -> Result<TileSheet, String> {
+    let image = tileset.children()
+        .find(|child| child.has_tag_name("image"))
+        .and_then(|image| image.attribute("source"))
+        .ok_or("seuls les tilesets basés sur une image sont supportés")?;
+    tile_sheet(
+        image,
+        UVec2::new(xml_number(tileset, "tilewidth")?, xml_number(tileset, "tileheight")?),
+        xml_number(tileset, "tilecount")?,
+        xml_number(tileset, "columns")?,
+        xml_number_or(tileset, "spacing", 0)?,
+        xml_number_or(tileset, "margin", 0)?,
+    )
+}
+
+fn xml_properties(node: Node) -> Properties {
+    node.children()
+        .filter(|child| child.has_tag_name("properties"))
+        .flat_map(|properties| properties.children().filter(|child| child.has_tag_name("property")))
+        .filter_map(|property| {
+            // Multiline strings are stored as text instead of a `value` attribute.
+            let value = property.attribute("value").or_else(|| property.text())?;
+            Some((property.attribute("name")?.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Flattens groups, a hidden group hides its layers.
+fn xml_layers(node: Node, parent_visible: bool, layers: &mut Vec<TiledLayer>) -> Result<(), String> {
+    let visible = parent_visible && node.attribute("visible") != Some("0");
+    let content = match node.tag_name().name() {
+        "group" => {
+            for child in node.children().filter(Node::is_element) {
+                xml_layers(child, visible, layers)?;
+            }
+            return Ok(());
+        }
+        "layer" => {
+            let data = node.children()
+                .find(|child| child.has_tag_name("data"))
+                .ok_or("couche sans <data>")?;
+            LayerContent::Tiles(xml_tiles(data)?)
+        }
+        "objectgroup" => LayerContent::Objects(
+            node.children()
+                .filter(|child| child.has_tag_name("object"))
+                .map(xml_object)
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => return Ok(()),
+    };
+    layers.push(TiledLayer { visible, properties: xml_properties(node), content });
+    Ok(())
+}
+
+fn xml_tiles(data: Node) -> Result<Vec<u32>, String> {
+    match data.attribute("encoding") {
+        Some("csv") => data.text()
+            .unwrap_or_default()
+            .split(',')
+            .map(|gid| gid.trim().parse().map_err(|_| format!("tuile invalide: {}", gid.trim())))
+            .collect(),
+        None => data.children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| xml_number_or(tile, "gid", 0))
+            .collect(),
+        Some(encoding) => Err(unsupported_encoding(encoding)),
+    }
+}
+
+fn xml_object(object: Node) -> Result<TiledObject, String> {
+    Ok(TiledObject {
+        name: object.attribute("name").unwrap_or_default().to_string(),
+        class: object.attribute("type").or_else(|| object.attribute("class")).unwrap_or_default().to_string(),
+        position: Vec2::new(xml_number(object, "x")?, xml_number(object, "y")?),
+        size: Vec2::new(xml_number_or(object, "width", 0.0)?, xml_number_or(object, "height", 0.0)?),
+        tile: object.attribute("gid").is_some(),
+        properties: xml_properties(object),
+    })
+}
+
+#[derive(Deserialize)]
+struct JsonMap {
+    width: u32,
+    height: u32,
+    tilewidth: f32,
+    tileheight: f32,
+    orientation: Option<String>,
+    #[serde(default)]
+    infinite: bool,
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+/// Embedded in a map, or the content of a `.tsj` file without `firstgid` and `source`.
+#[derive(Deserialize)]
+struct JsonTileset {
+    #[serde(default)]
+    firstgid: u32,
+    source: Option<String>,
+    image: Option<String>,
+    #[serde(default)]
+    tilewidth: u32,
+    #[serde(default)]
+    tileheight: u32,
+    #[serde(default)]
+    tilecount: u32,
+    #[serde(default)]
+    columns: u32,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    margin: u32,
+}
+
+#[derive(Deserialize)]
+struct JsonLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default = "json_visible")]
+    visible: bool,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+    encoding: Option<String>,
+    data: Option<Value>,
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+fn json_visible() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    gid: Option<u32>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    value: Value,
+}
+
+fn json_properties(properties: Vec<JsonProperty>) -> Properties {
+    properties.into_iter()
+        .map(|property| {
+            let value = match property.value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            (property.name, value)
+        })
+        .collect()
+}
+
+fn parse_tmj(bytes: &[u8]) -> Result<TiledMap, String> {
+    let map: JsonMap = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+    check_map_kind(map.orientation.as_deref(), map.infinite)?;
+
+    let tilesets = map.tilesets.into_iter()
+        .map(|tileset| {
+            let first_gid = tileset.firstgid;
+            let source = match tileset.source.clone() {
+                Some(source) => TilesetSource::External(source),
+                None => TilesetSource::Embedded(json_tile_sheet(tileset)?),
+            };
+            Ok(TiledTileset { first_gid, source })
+        })
+        .collect::<Result<_, String>>()?;
+
+    let mut layers = Vec::new();
+    for layer in map.layers {
+        json_layers(layer, true, &mut layers)?;
+    }
+
+    Ok(TiledMap {
+        width: map.width,
+        height: map.height,
+        tile_size: Vec2::new(map.tilewidth, map.tileheight),
+        tilesets,
+        layers,
+    })
+}
+
+fn parse_tsj(bytes: &[u8]) -> Result<TileSheet, String> {
+    json_tile_sheet(serde_json::from_slice(bytes).map_err(|e| e.to_string())?)
+}
+
+fn json_tile_sheet(tileset: JsonTileset) -> Result<TileSheet, String> {
+    let image = tileset.image.ok_or("seuls les tilesets basés sur une image sont supportés")?;
+    tile_sheet(
+        &image,
+        UVec2::new(tileset.tilewidth, tileset.tileheight),
+        tileset.tilecount,
+        tileset.columns,
+        tileset.spacing,
+        tileset.margin,
+    )
+}
+
+fn json_layers(layer: JsonLayer, parent_visible: bool, layers: &mut Vec<TiledLayer>) -> Result<(), String> {
+    let visible = parent_visible && layer.visible;
+    let content = match layer.kind.as_str() {
+        "group" => {
+            for child in layer.layers {
+                json_layers(child, visible, layers)?;
+            }
+            return Ok(());
+        }
+        "tilelayer" => {
+            if let Some(encoding) = layer.encoding.as_deref().filter(|encoding| *encoding != "csv") {
+                return Err(unsupported_encoding(encoding));
+            }
+            let data = layer.data.ok_or("couche sans data")?;
+            LayerContent::Tiles(serde_json::from_value(data).map_err(|e| e.to_string())?)
+        }
+        "objectgroup" => LayerContent::Objects(
+            layer.objects.into_iter()
+                .map(|object| TiledObject {
+                    name: object.name,
+                    class: if object.kind.is_empty() { object.class } else { object.kind },
+                    position: Vec2::new(object.x, object.y),
+                    size: Vec2::new(object.width, object.height),
+                    tile: object.gid.is_some(),
+                    properties: json_properties(object.properties),
+                })
+                .collect(),
+        ),
+        _ => return Ok(()),
+    };
+    layers.push(TiledLayer { visible, properties: json_properties(layer.properties), content });
+    Ok(())
+}
+
+fn check_map_kind(orientation: Option<&str>, infinite: bool) -> Result<(), String> {
+    if orientation.is_some_and(|orientation| orientation != "orthogonal") {
+        return Err("seules les cartes orthogonales sont supportées".to_string());
+    }
+    if infinite {
+        return Err("les cartes infinies ne sont pas supportées".to_string());
+    }
+    Ok(())
+}
+
+fn unsupported_encoding(encoding: &str) -> String {
+    format!("encodage {encoding} non supporté, enregistrer les couches de tuiles en CSV")
+}
+
+fn tile_sheet(image: &str, tile_size: UVec2, tile_count: u32, columns: u32, spacing: u32, margin: u32) -> Result<TileSheet, String> {
+    if columns == 0 || tile_size.cmpeq(UVec2::ZERO).any() {
+        return Err(format!("tileset invalide: {image}"));
+    }
+    Ok(TileSheet {
+        image: image.to_string(),
+        tile_size,
+        columns,
+        rows: tile_count.div_ceil(columns),
+        spacing,
+        margin,
+    })
+}
+
+/// Makes the image path of `sheet` relative to the asset folder instead of `relative_to`.
+fn resolve_image(mut sheet: TileSheet, relative_to: &AssetPath) -> Result<TileSheet, LevelLoaderError> {
+    sheet.image = relative_to.resolve_embed(&sheet.image)
+        .map_err(|e| LevelLoaderError::Tiled(format!("{}: {e}", sheet.image)))?
+        .to_string();
+    Ok(sheet)
+}
+
+/// Converts Tiled maps into levels: visible tile layers are drawn, tile layers with a `collision`
+/// property become merged platforms, one-way with `one_way`, and objects are placed by class:
+/// `spawn_point`, `pickup` (`pickup`, `respawn_secs`), `monster_spawner` (`monster_type`, `respawn_secs`),
+/// `flag_base` (`team`), `kill_zone` and `bounds`, property values written in RON, e.g. `Health(50)`.
+#[derive(Default)]
+pub struct TiledLoader;
+
+impl AssetLoader for TiledLoader {
+    type Asset = LevelDefinition;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(LevelLoaderError::Io)?;
+        let map = match load_context.path().extension().and_then(|extension| extension.to_str()) {
+            Some("tmj") => parse_tmj(&bytes),
+            _ => parse_tmx(&bytes),
+        }.map_err(LevelLoaderError::Tiled)?;
+
+        let map_path = load_context.asset_path().clone();
+        let mut sheets = Vec::new();
+        for tileset in &map.tilesets {
+            let sheet = match &tileset.source {
+                TilesetSource::Embedded(sheet) => resolve_image(sheet.clone(), &map_path)?,
+                TilesetSource::External(source) => {
+                    let tileset_error = |e: String| LevelLoaderError::Tiled(format!("{source}: {e}"));
+                    let path = map_path.resolve_embed(source).map_err(|e| tileset_error(e.to_string()))?;
+                    let bytes = load_context.read_asset_bytes(path.clone()).await.map_err(|e| tileset_error(e.to_string()))?;
+                    let sheet = if source.ends_with(".tsx") { parse_tsx(&bytes) } else { parse_tsj(&bytes) };
+                    resolve_image(sheet.map_err(tileset_error)?, &path)?
+                }
+            };
+            sheets.push((tileset.first_gid, sheet));
+        }
+
+        let level = map.into_level(sheets).map_err(LevelLoaderError::Tiled)?;
+        level.validate().map_err(LevelLoaderError::Invalid)?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &TILED_EXTENSIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_mode::component::Team;
+    use crate::pickup::component::Pickup;
+
+    const SMALL_TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map orientation="orthogonal" width="4" height="3" tilewidth="10" tileheight="10" infinite="0">
+ <tileset firstgid="1" tilewidth="10" tileheight="10" tilecount="2" columns="2">
+  <image source="tiles.png" width="20" height="10"/>
+ </tileset>
+ <layer name="decor">
+  <data encoding="csv">
+0,2147483650,0,0,
+0,0,0,0,
+0,0,0,0
+</data>
+ </layer>
+ <layer name="ground" visible="0">
+  <properties>
+   <property name="collision" type="bool" value="true"/>
+  </properties>
+  <data encoding="csv">
+0,0,0,0,
+0,0,0,0,
+1,1,1,1
+</data>
+ </layer>
+ <objectgroup name="gameplay">
+  <object type="spawn_point" x="5" y="15"><point/></object>
+  <object type="flag_base" gid="1" x="30" y="20" width="10" height="10">
+   <properties>
+    <property name="team" value="Blue"/>
+   </properties>
+  </object>
+  <object type="kill_zone" x="0" y="20" width="40" height="10"/>
+  <object type="note" x="0" y="0" width="5" height="5"/>
+ </objectgroup>
+</map>
+"#;
+
+    fn grid(rows: &[&str]) -> (Vec<bool>, u32, u32) {
+        let solid = rows.iter().flat_map(|row| row.chars().map(|cell| cell == '#')).collect();
+        (solid, rows[0].len() as u32, rows.len() as u32)
+    }
+
+    fn embedded_sheets(map: &TiledMap) -> Vec<(u32, TileSheet)> {
+        map.tilesets.iter()
+            .map(|tileset| match &tileset.source {
+                TilesetSource::Embedded(sheet) => (tileset.first_gid, sheet.clone()),
+                TilesetSource::External(source) => panic!("tileset externe inattendu: {source}"),
+            })
+            .collect()
+    }
+
+    fn load(map: TiledMap) -> LevelDefinition {
+        let sheets = embedded_sheets(&map);
+        let level = map.into_level(sheets).unwrap();
+        level.validate().unwrap();
+        level
+    }
+
+    #[test]
+    fn l_shaped_cells_merge_into_a_column_and_a_row() {
+        let (solid, width, height) = grid(&[
+            "#..",
+            "#..",
+            "###",
+        ]);
+
+        assert_eq!(merge_cells(&solid, width, height), vec![
+            URect::new(0, 0, 1, 3),
+            URect::new(1, 2, 3, 3),
+        ]);
+    }
+
+    #[test]
+    fn t_shaped_cells_merge_into_a_row_and_a_column() {
+        let (solid, width, height) = grid(&[
+            "###",
+            ".#.",
+            ".#.",
+        ]);
+
+        assert_eq!(merge_cells(&solid, width, height), vec![
+            URect::new(0, 0, 3, 1),
+            URect::new(1, 1, 2, 3),
+        ]);
+    }
+
+    #[test]
+    fn tmx_maps_convert_to_y_up_levels_centered_on_the_origin() {
+        let level = load(parse_tmx(SMALL_TMX.as_bytes()).unwrap());
+
+        assert_eq!(level.platforms.len(), 1);
+        let ground = &level.platforms[0];
+        assert_eq!((ground.position, ground.size), (Vec2::new(0.0, -10.0), Vec2::new(40.0, 10.0)));
+        assert!(!ground.one_way && !ground.visible);
+
+        assert_eq!(level.spawn_points, vec![Vec2::new(-15.0, 0.0)]);
+        assert_eq!(level.kill_zones.len(), 1);
+        assert_eq!((level.kill_zones[0].min, level.kill_zones[0].max), (Vec2::new(-20.0, -15.0), Vec2::new(20.0, -5.0)));
+        assert_eq!((level.bounds.min, level.bounds.max), (Vec2::new(-20.0, -15.0), Vec2::new(20.0, 15.0)));
+
+        // Tile objects hang above their anchor.
+        assert_eq!(level.flag_bases.len(), 1);
+        assert_eq!((level.flag_bases[0].team, level.flag_bases[0].position), (Team::Blue, Vec2::new(15.0, 0.0)));
+    }
+
+    #[test]
+    fn tmx_tiles_keep_their_index_and_flip_flags() {
+        let level = load(parse_tmx(SMALL_TMX.as_bytes()).unwrap());
+
+        // The hidden collision layer is not drawn.
+        assert_eq!(level.tile_layers.len(), 1);
+        let tiles = &level.tile_layers[0].tiles;
+        assert_eq!(tiles.len(), 1);
+        assert_eq!((tiles[0].index, tiles[0].flip_x, tiles[0].flip_y), (1, true, false));
+        assert_eq!(tiles[0].position, Vec2::new(-5.0, 10.0));
+        assert_eq!((level.tile_sheets[0].columns, level.tile_sheets[0].rows), (2, 1));
+    }
+
+    #[test]
+    fn tmj_maps_read_groups_classes_and_typed_properties() {
+        let tmj = r#"{
+            "orientation": "orthogonal", "width": 2, "height": 2, "tilewidth": 16, "tileheight": 16,
+            "tilesets": [{ "firstgid": 1, "image": "tiles.png", "tilewidth": 16, "tileheight": 16, "tilecount": 2, "columns": 2 }],
+            "layers": [
+                { "type": "tilelayer", "data": [0, 0, 2, 2], "properties": [
+                    { "name": "collision", "type": "bool", "value": true },
+                    { "name": "one_way", "type": "bool", "value": true }
+                ] },
+                { "type": "group", "layers": [{ "type": "objectgroup", "objects": [
+                    { "class": "spawn_point", "x": 8, "y": 8, "point": true },
+                    { "type": "pickup", "x": 24, "y": 8, "properties": [
+                        { "name": "pickup", "type": "string", "value": "Health(25)" },
+                        { "name": "respawn_secs", "type": "float", "value": 12.5 }
+                    ] }
+                ] }] }
+            ]
+        }"#;
+
+        let level = load(parse_tmj(tmj.as_bytes()).unwrap());
+
+        assert_eq!(level.platforms.len(), 1);
+        assert!(level.platforms[0].one_way);
+        assert_eq!((level.platforms[0].position, level.platforms[0].size), (Vec2::new(0.0, -8.0), Vec2::new(32.0, 16.0)));
+        assert_eq!(level.spawn_points, vec![Vec2::new(-8.0, 8.0)]);
+        assert_eq!(level.pickups.len(), 1);
+        assert_eq!((&level.pickups[0].pickup, level.pickups[0].respawn_secs), (&Pickup::Health(25), 12.5));
+    }
+
+    #[test]
+    fn encoded_tile_data_is_refused() {
+        let base64 = SMALL_TMX.replacen(r#"<data encoding="csv">"#, r#"<data encoding="base64">"#, 1);
+
+        assert!(parse_tmx(base64.as_bytes()).unwrap_err().contains("CSV"));
+    }
+
+    #[test]
+    fn shipped_arena_map_is_a_valid_level() {
+        let level = load(parse_tmx(include_bytes!("../../../assets/levels/arena.tmx")).unwrap());
+
+        assert_eq!(level.platforms.iter().filter(|platform| platform.one_way).count(), 3);
+        assert_eq!(level.spawn_points.len(), 4);
+        assert_eq!(level.flag_bases.len(), 2);
+    }
+}
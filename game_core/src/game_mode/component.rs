@@ -0,0 +1,90 @@
+use bevy::prelude::{Component, States};
+use bevy_renet2::prelude::ClientId;
+use serde::{Deserialize, Serialize};
+
+/// Match lifecycle of the scoring modes: Warmup → InProgress → Ended → Warmup.
+#[derive(States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MatchPhase {
+    /// Free play, not scored, until enough players joined and the countdown ran out.
+    #[default]
+    Warmup,
+    InProgress,
+    /// Results are shown; players can move but not shoot.
+    Ended,
+}
+
+/// Side of a player in the team modes; players without one play for themselves.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Team {
+    Red,
+    Blue,
+}
+
+impl Team {
+    pub fn other(&self) -> Self {
+        match self {
+            Team::Red => Team::Blue,
+            Team::Blue => Team::Red,
+        }
+    }
+}
+
+/// Who a score belongs to: a player in free-for-all, a team otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScoreOwner {
+    Player(ClientId),
+    Team(Team),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub owner: ScoreOwner,
+    pub score: i32,
+}
+
+/// Per-player statistics of the current match.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub kills: u32,
+    pub deaths: u32,
+    /// Kills of another player this one damaged first.
+    pub assists: u32,
+    /// Health removed from other players and monsters.
+    pub damage_dealt: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreboardEntry {
+    pub id: ClientId,
+    pub name: String,
+    pub team: Option<Team>,
+    pub stats: PlayerStats,
+}
+
+/// Single best score, `None` without any score or on a tie.
+pub fn leader(scores: &[ScoreEntry]) -> Option<&ScoreEntry> {
+    let best = scores.iter().max_by_key(|entry| entry.score)?;
+    let tied = scores.iter().filter(|entry| entry.score == best.score).count() > 1;
+    (!tied).then_some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(client_id: ClientId, score: i32) -> ScoreEntry {
+        ScoreEntry { owner: ScoreOwner::Player(client_id), score }
+    }
+
+    #[test]
+    fn best_score_leads() {
+        let scores = [entry(1, 3), entry(2, 5), entry(3, -1)];
+        assert_eq!(leader(&scores), Some(&scores[1]));
+    }
+
+    #[test]
+    fn tie_or_no_score_has_no_leader() {
+        assert_eq!(leader(&[entry(1, 5), entry(2, 5), entry(3, 1)]), None);
+        assert_eq!(leader(&[]), None);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::game_mode::component::Team;
+use crate::weapon::texture::WeaponTextureType;
+use bevy::math::Vec2;
+use bevy::prelude::{Color, Commands, Component, Entity, GlobalTransform, Name, Sprite, Timer, TimerMode, Transform};
+use bevy_rapier2d::dynamics::Velocity;
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, Sensor};
+use bevy_renet2::prelude::ClientId;
+use serde::{Deserialize, Serialize};
+
+/// Horizontal speed multiplier of a player carrying a flag.
+pub const FLAG_CARRIER_SPEED_FACTOR: f32 = 0.75;
+/// Only weapon a flag carrier can draw.
+pub const FLAG_CARRIER_WEAPON: WeaponTextureType = WeaponTextureType::Pistol;
+/// Time a dropped flag stays on the ground before going back to its base.
+pub const FLAG_RETURN_SECS: f32 = 20.0;
+/// Where a carried flag is drawn, relative to its carrier.
+pub const FLAG_CARRY_OFFSET: Vec2 = Vec2::new(0.0, 40.0);
+const FLAG_SIZE: Vec2 = Vec2::new(24.0, 32.0);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Flag {
+    pub team: Team,
+}
+
+/// Where a flag stands when nobody took it.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FlagHome(pub Vec2);
+
+#[derive(Component, Debug)]
+pub enum FlagState {
+    AtBase,
+    /// Follows `player`; `id` outlives the entity when the carrier disconnects.
+    Carried { player: Entity, id: ClientId },
+    Dropped(Timer),
+}
+
+impl FlagState {
+    pub fn dropped() -> Self {
+        FlagState::Dropped(Timer::from_seconds(FLAG_RETURN_SECS, TimerMode::Once))
+    }
+}
+
+/// Put on a player holding the flag of `team`; slows it down and restricts its weapons.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FlagCarrier {
+    pub team: Team,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlagBase {
+    pub team: Team,
+    pub position: Vec2,
+}
+
+/// Flag state transition, announced to every client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FlagEvent {
+    Taken { by: ClientId },
+    /// The carrier died or left; the flag goes back to its base after `return_in_secs`.
+    Dropped { by: ClientId, translation: [f32; 3], return_in_secs: f32 },
+    /// `by` is the defender who touched it, `None` when its timer ran out or it fell out of the level;
+    /// `carrier` is set when it went home straight from the player carrying it.
+    Returned { by: Option<ClientId>, carrier: Option<ClientId> },
+    Captured { by: ClientId },
+    /// Back at its base for the start of a match, any carry cancelled.
+    Reset,
+}
+
+pub fn spawn_flag(commands: &mut Commands, flag_base: FlagBase) -> Entity {
+    commands.spawn((
+        Name::new("Flag"),
+        Flag { team: flag_base.team },
+        FlagHome(flag_base.position),
+        FlagState::AtBase,
+        Transform::from_translation(flag_base.position.extend(0.0)),
+        GlobalTransform::default(),
+        Sensor,
+        Collider::cuboid(FLAG_SIZE.x / 2.0, FLAG_SIZE.y / 2.0),
+        ActiveEvents::COLLISION_EVENTS,
+    )).id()
+}
+
+pub fn flag_sprite(team: Team) -> Sprite {
+    let color = match team {
+        Team::Red => Color::srgb(0.9, 0.15, 0.15),
+        Team::Blue => Color::srgb(0.15, 0.35, 0.9),
+    };
+    Sprite::from_color(color, FLAG_SIZE)
+}
+
+/// Applied after the shared movement step, on the server and in the client prediction alike.
+pub fn slow_flag_carrier(velocity: &mut Velocity) {
+    velocity.linvel.x *= FLAG_CARRIER_SPEED_FACTOR;
+}
@@ -1,5 +1,9 @@
+pub mod config;
 pub mod network;
 pub mod decor;
+pub mod game_mode;
+pub mod monster;
+pub mod pickup;
 pub mod player;
 pub mod texture;
 pub mod weapon;
@@ -0,0 +1,29 @@
+use crate::monster::component::{Monster, MonsterAi};
+use crate::player::component::{AnimationIndices, AnimationTimer};
+use crate::texture::component::SpriteFrame;
+use bevy::prelude::{Query, Res, With};
+use bevy::time::Time;
+use bevy_rapier2d::dynamics::Velocity;
+
+/// Walk cycle while the monster moves; the sheet faces right, so left-facing monsters are flipped.
+pub fn animate_monsters(
+    time: Res<Time>,
+    monster_query: Query<(&AnimationIndices, &mut AnimationTimer, &mut SpriteFrame, &MonsterAi, &Velocity), With<Monster>>,
+) {
+    for (indices, mut animation_timer, mut sprite_frame, ai, velocity) in monster_query {
+        sprite_frame.flip_x = ai.direction < 0.0;
+
+        if velocity.linvel.x.abs() < 1.0 {
+            sprite_frame.index = indices.first as usize;
+            continue;
+        }
+
+        if animation_timer.0.tick(time.delta()).just_finished() {
+            sprite_frame.index = if sprite_frame.index >= indices.last as usize {
+                indices.first as usize
+            } else {
+                sprite_frame.index + 1
+            };
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::monster::component::{Monster, MonsterAi, MonsterType};
+use crate::monster::texture::{MonsterTextureEntity, MonsterTextures};
+use crate::player::component::{AnimationTimer, Health};
+use crate::texture::component::SpriteFrame;
+use crate::texture::entity::TextureHandleMap;
+use crate::texture::system::{handle_from_texture, sprite_from_frame};
+use bevy::asset::Assets;
+use bevy::image::TextureAtlasLayout;
+use bevy::math::Vec3;
+use bevy::prelude::{Commands, Entity, GlobalTransform, Name, Res, ResMut, Sprite, Timer, TimerMode, Transform};
+
+pub fn spawn_monster_entity(
+    commands: &mut Commands,
+    monster_type: MonsterType,
+    position: Vec3,
+) -> Entity {
+    let stats = monster_type.stats();
+    let monster_texture = MonsterTextureEntity::new(&monster_type);
+
+    commands.spawn((
+        Name::new("Monster"),
+        Monster,
+        monster_type,
+        SpriteFrame {
+            index: monster_texture.animation_indices.first as usize,
+            ..Default::default()
+        },
+        monster_texture.animation_indices,
+        AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
+        Health { current: stats.max_health, max: stats.max_health },
+        MonsterAi::new(&stats),
+        Transform::from_translation(position),
+        GlobalTransform::default(),
+    )).id()
+}
+
+pub fn monster_sprite(
+    monster_type: &MonsterType,
+    sprite_frame: SpriteFrame,
+    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
+    monster_textures: &Res<MonsterTextures>,
+) -> Sprite {
+    let (image, layout) = handle_from_texture(
+        monster_type,
+        texture_atlas_layouts,
+        monster_textures,
+        MonsterTextureEntity::new,
+        MonsterTextures::get_handle,
+    );
+    sprite_from_frame(image, layout, sprite_frame)
+}
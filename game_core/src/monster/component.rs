@@ -0,0 +1,143 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Entity, Timer, TimerMode};
+use bevy_rapier2d::dynamics::{GravityScale, LockedAxes, RigidBody, Velocity};
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, Friction};
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize, Default)]
+pub enum MonsterType {
+    #[default]
+    Blob,
+    Brute,
+}
+
+/// Tuning of a monster type; distances in pixels, speeds in pixels per second.
+#[derive(Debug, Clone, Copy)]
+pub struct MonsterStats {
+    pub max_health: u32,
+    pub patrol_speed: f32,
+    pub chase_speed: f32,
+    pub sight_range: f32,
+    pub melee_range: f32,
+    pub melee_damage: u32,
+    pub attack_cooldown_secs: f32,
+    pub half_size: Vec2,
+}
+
+impl MonsterType {
+    pub fn stats(&self) -> MonsterStats {
+        match self {
+            MonsterType::Blob => MonsterStats {
+                max_health: 40,
+                patrol_speed: 60.0,
+                chase_speed: 140.0,
+                sight_range: 350.0,
+                melee_range: 55.0,
+                melee_damage: 8,
+                attack_cooldown_secs: 0.8,
+                half_size: Vec2::new(28.0, 22.0),
+            },
+            MonsterType::Brute => MonsterStats {
+                max_health: 150,
+                patrol_speed: 40.0,
+                chase_speed: 90.0,
+                sight_range: 450.0,
+                melee_range: 75.0,
+                melee_damage: 25,
+                attack_cooldown_secs: 1.5,
+                half_size: Vec2::new(30.0, 40.0),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct Monster;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MonsterBehavior {
+    Patrol,
+    Chase(Entity),
+    Attack(Entity),
+}
+
+/// Time a monster lets an explosion push it around before steering again.
+pub const MONSTER_KNOCKBACK_STUN_SECS: f32 = 0.5;
+
+/// Server-side AI state; `direction` is -1.0 or 1.0 and also drives the sprite orientation.
+#[derive(Debug, Component)]
+pub struct MonsterAi {
+    pub behavior: MonsterBehavior,
+    pub direction: f32,
+    pub attack_cooldown: Timer,
+    /// Runs after a knockback; the monster does not steer until it ends.
+    pub stun: Timer,
+}
+
+impl MonsterAi {
+    pub fn new(stats: &MonsterStats) -> Self {
+        let mut attack_cooldown = Timer::from_seconds(stats.attack_cooldown_secs, TimerMode::Once);
+        attack_cooldown.tick(attack_cooldown.duration());
+        let mut stun = Timer::from_seconds(MONSTER_KNOCKBACK_STUN_SECS, TimerMode::Once);
+        stun.tick(stun.duration());
+        Self {
+            behavior: MonsterBehavior::Patrol,
+            direction: if fastrand::bool() { 1.0 } else { -1.0 },
+            attack_cooldown,
+            stun,
+        }
+    }
+
+    pub fn knock_back(&mut self) {
+        self.stun.reset();
+    }
+
+    pub fn stunned(&self) -> bool {
+        !self.stun.finished()
+    }
+}
+
+/// Level placement of a monster.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MonsterSpawn {
+    pub monster_type: MonsterType,
+    pub position: Vec2,
+    pub respawn_secs: f32,
+}
+
+/// Keeps one monster alive at its position, spawning a new one `respawn` after the last died.
+#[derive(Debug, Component)]
+pub struct MonsterSpawner {
+    pub monster_type: MonsterType,
+    pub respawn: Timer,
+    pub monster: Option<Entity>,
+}
+
+impl MonsterSpawner {
+    pub fn new(monster_type: MonsterType, respawn_secs: f32) -> Self {
+        let mut respawn = Timer::from_seconds(respawn_secs, TimerMode::Once);
+        respawn.tick(respawn.duration());
+        Self { monster_type, respawn, monster: None }
+    }
+}
+
+pub fn monster_physics(monster_type: &MonsterType) -> (
+    RigidBody,
+    LockedAxes,
+    Velocity,
+    Collider,
+    GravityScale,
+    Friction,
+    ActiveEvents,
+) {
+    let half_size = monster_type.stats().half_size;
+    (
+        RigidBody::Dynamic,
+        LockedAxes::ROTATION_LOCKED,
+        Velocity::zero(),
+        Collider::cuboid(half_size.x, half_size.y),
+        GravityScale(2.5),
+        Friction::coefficient(0.0),
+        ActiveEvents::COLLISION_EVENTS,
+    )
+}
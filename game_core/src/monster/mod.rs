@@ -0,0 +1,5 @@
+pub mod component;
+pub mod texture;
+pub mod command;
+pub mod animation;
+pub mod wave;
@@ -0,0 +1,69 @@
+use crate::make_monster_texture;
+use crate::monster::component::MonsterType;
+use crate::player::component::AnimationIndices;
+use crate::texture::entity::{HasTextureEntityType, TextureHandleMap};
+use crate::texture::system::texture;
+use bevy::math::UVec2;
+use bevy::prelude::{Handle, Image, Resource, TextureAtlasLayout};
+use std::collections::HashMap;
+
+pub const MONSTERS: &str = "textures/monsters/monsters.png";
+
+#[derive(Resource, Clone)]
+pub struct MonsterTextures(pub HashMap<MonsterType, Handle<Image>>);
+
+impl TextureHandleMap<MonsterType> for MonsterTextures {
+    fn get_handle(&self, key: MonsterType) -> Option<Handle<Image>> {
+        self.0.get(&key).cloned()
+    }
+}
+
+pub struct MonsterTextureEntity {
+    pub texture_atlas_layout: TextureAtlasLayout,
+    pub animation_indices: AnimationIndices,
+    pub texture_path: String,
+    pub monster_type: MonsterType,
+}
+
+const MONSTER_LAYOUT_SIZE: UVec2 = UVec2::new(344, 136);
+const MONSTER_FRAME_PADDING: u32 = 8;
+const MONSTER_FRAME_COUNT: u32 = 4;
+
+const BLOB_SIZE: UVec2 = UVec2::new(64, 48);
+const BLOB_START_MIN: UVec2 = UVec2::new(0, 0);
+
+const BRUTE_SIZE: UVec2 = UVec2::new(80, 80);
+const BRUTE_START_MIN: UVec2 = UVec2::new(0, 56);
+
+impl MonsterTextureEntity {
+    pub fn new(monster_type: &MonsterType) -> Self {
+        match monster_type {
+            MonsterType::Blob => make_monster_texture!(
+                MonsterType::Blob,
+                BLOB_START_MIN,
+                BLOB_SIZE
+            ),
+            MonsterType::Brute => make_monster_texture!(
+                MonsterType::Brute,
+                BRUTE_START_MIN,
+                BRUTE_SIZE
+            ),
+        }
+    }
+
+    pub fn all() -> Vec<(MonsterType, &'static str)> {
+        vec![
+            (MonsterType::Blob, MONSTERS),
+            (MonsterType::Brute, MONSTERS),
+        ]
+    }
+}
+
+impl HasTextureEntityType<MonsterType> for MonsterTextureEntity {
+    fn texture_atlas_layout(&self) -> TextureAtlasLayout {
+        self.texture_atlas_layout.clone()
+    }
+    fn texture_entity_type(&self) -> MonsterType {
+        self.monster_type
+    }
+}
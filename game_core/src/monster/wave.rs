@@ -0,0 +1,148 @@
+use crate::monster::component::MonsterType;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// File under the asset directory describing the waves of the survival mode.
+pub const WAVE_DEFINITIONS_PATH: &str = "waves.ron";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaveDefinition {
+    /// How many monsters of each type the wave holds.
+    pub monsters: BTreeMap<MonsterType, u32>,
+    /// Delay between two monsters of the wave appearing.
+    pub spawn_interval_secs: f32,
+}
+
+#[derive(Debug, Clone, Resource, Deserialize)]
+pub struct WaveDefinitions {
+    pub intermission_secs: f32,
+    /// Extra monsters per wave past the last defined one, as a fraction of that wave.
+    pub growth_per_extra_wave: f32,
+    pub waves: Vec<WaveDefinition>,
+}
+
+impl WaveDefinitions {
+    /// Reads `waves.ron` from the asset directory.
+    pub fn load(asset_path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = asset_path.as_ref().join(WAVE_DEFINITIONS_PATH);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("impossible de lire {}: {e}", path.display()))?;
+        let definitions: Self = ron::from_str(&content)
+            .map_err(|e| format!("définitions de vagues {} invalides: {e}", path.display()))?;
+        definitions.validate().map_err(|e| format!("{}: {e}", path.display()))?;
+        Ok(definitions)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+        if self.waves.is_empty() {
+            return Err("au moins une vague est requise".to_string());
+        }
+        if !(positive(self.intermission_secs) && self.growth_per_extra_wave.is_finite() && self.growth_per_extra_wave >= 0.0) {
+            return Err("intermission_secs doit être positif et growth_per_extra_wave positif ou nul".to_string());
+        }
+        for (index, wave) in self.waves.iter().enumerate() {
+            if !positive(wave.spawn_interval_secs) || wave.monsters.values().sum::<u32>() == 0 {
+                return Err(format!("vague {} invalide: {wave:?}", index + 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Waves past the last defined one repeat it with more monsters.
+    fn wave(&self, number: u32) -> (&WaveDefinition, f32) {
+        let index = (number.max(1) - 1) as usize;
+        let last = self.waves.len() - 1;
+        let extra_waves = index.saturating_sub(last) as f32;
+        (&self.waves[index.min(last)], 1.0 + self.growth_per_extra_wave * extra_waves)
+    }
+
+    /// Monsters of wave `number` (starting at 1) in spawn order, the types interleaved.
+    pub fn monsters(&self, number: u32) -> Vec<MonsterType> {
+        let (wave, scale) = self.wave(number);
+        let mut remaining: Vec<(MonsterType, u32)> = wave.monsters.iter()
+            .map(|(monster_type, count)| (*monster_type, (*count as f32 * scale).ceil() as u32))
+            .collect();
+
+        let mut monsters = Vec::new();
+        while remaining.iter().any(|(_, count)| *count > 0) {
+            for (monster_type, count) in remaining.iter_mut().filter(|(_, count)| *count > 0) {
+                monsters.push(*monster_type);
+                *count -= 1;
+            }
+        }
+        monsters
+    }
+
+    pub fn spawn_interval_secs(&self, number: u32) -> f32 {
+        self.wave(number).0.spawn_interval_secs
+    }
+}
+
+/// Survival mode progress as shown to clients.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WaveProgress {
+    Intermission { next_wave_in_secs: f32 },
+    InProgress { remaining_monsters: u32 },
+    /// Every player died; the game restarts from the first wave afterwards.
+    GameOver { restart_in_secs: f32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MonsterType::{Blob, Brute};
+
+    fn definitions() -> WaveDefinitions {
+        ron::from_str("(
+            intermission_secs: 10.0,
+            growth_per_extra_wave: 0.5,
+            waves: [
+                (monsters: {Blob: 2}, spawn_interval_secs: 1.5),
+                (monsters: {Blob: 3, Brute: 1}, spawn_interval_secs: 1.0),
+            ],
+        )").unwrap()
+    }
+
+    #[test]
+    fn monster_types_are_interleaved() {
+        assert_eq!(definitions().monsters(1), vec![Blob, Blob]);
+        assert_eq!(definitions().monsters(2), vec![Blob, Brute, Blob, Blob]);
+    }
+
+    #[test]
+    fn waves_past_the_last_one_grow() {
+        let definitions = definitions();
+        assert_eq!(definitions.monsters(3), vec![Blob, Brute, Blob, Brute, Blob, Blob, Blob]);
+        assert_eq!(definitions.monsters(4).len(), 8);
+        assert_eq!(definitions.spawn_interval_secs(4), 1.0);
+        assert_eq!(definitions.monsters(0), definitions.monsters(1));
+    }
+
+    #[test]
+    fn shipped_waves_are_valid() {
+        let definitions: WaveDefinitions = ron::de::from_bytes(include_bytes!("../../../assets/waves.ron")).unwrap();
+        assert!(definitions.validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_waves_are_refused() {
+        let mut no_waves = definitions();
+        no_waves.waves.clear();
+        assert!(no_waves.validate().is_err());
+
+        let mut shrinking = definitions();
+        shrinking.growth_per_extra_wave = -0.5;
+        assert!(shrinking.validate().is_err());
+
+        let mut empty_wave = definitions();
+        empty_wave.waves[1].monsters = BTreeMap::from([(Blob, 0)]);
+        assert!(empty_wave.validate().is_err());
+
+        let mut no_interval = definitions();
+        no_interval.waves[0].spawn_interval_secs = 0.0;
+        assert!(no_interval.validate().is_err());
+    }
+}
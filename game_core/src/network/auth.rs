@@ -0,0 +1,144 @@
+use crate::player::texture::PlayerTextureType;
+use bevy_renet2::netcode::{generate_random_bytes, ConnectToken, NetcodeError, TokenGenerationError, NETCODE_KEY_BYTES, NETCODE_USER_DATA_BYTES};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::time::Duration;
+
+pub const PRIVATE_KEY_PATH: &str = "private_key.bin";
+pub const CONNECT_TOKEN_PATH: &str = "connect_token.bin";
+const CONNECT_TOKEN_EXPIRE_SECONDS: u64 = 300;
+const CLIENT_TIMEOUT_SECONDS: i32 = 15;
+
+/// Player choices carried in the connect token, read back by the server on connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerUserData {
+    pub name: String,
+    pub player_texture_type: PlayerTextureType,
+}
+
+impl PlayerUserData {
+    pub fn to_user_data(&self) -> std::io::Result<[u8; NETCODE_USER_DATA_BYTES]> {
+        let bytes = bincode::serialize(self).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if bytes.len() > NETCODE_USER_DATA_BYTES {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("données joueur de {} octets, {NETCODE_USER_DATA_BYTES} maximum", bytes.len()),
+            ));
+        }
+
+        let mut user_data = [0u8; NETCODE_USER_DATA_BYTES];
+        user_data[..bytes.len()].copy_from_slice(&bytes);
+        Ok(user_data)
+    }
+
+    pub fn from_user_data(user_data: &[u8; NETCODE_USER_DATA_BYTES]) -> Option<Self> {
+        bincode::deserialize(user_data).ok()
+    }
+}
+
+pub fn parse_player_texture_type(name: &str) -> Option<PlayerTextureType> {
+    match name.to_ascii_lowercase().as_str() {
+        "rick1" => Some(PlayerTextureType::Rick1),
+        "rick2" => Some(PlayerTextureType::Rick2),
+        _ => None,
+    }
+}
+
+/// Random client id, so two clients started at the same instant never collide.
+pub fn new_client_id() -> u64 {
+    u64::from_le_bytes(generate_random_bytes())
+}
+
+/// Reads the server private key, creating a random one on first use.
+pub fn load_or_create_private_key(path: impl AsRef<Path>) -> std::io::Result<[u8; NETCODE_KEY_BYTES]> {
+    let path = path.as_ref();
+    if path.exists() {
+        let bytes = std::fs::read(path)?;
+        return bytes.try_into().map_err(|bytes: Vec<u8>| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("clé privée de {} octets, {NETCODE_KEY_BYTES} attendus", bytes.len()),
+            )
+        });
+    }
+
+    // Anyone able to read the key can mint connect tokens, so only its owner may; never overwrite one written meanwhile.
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let private_key: [u8; NETCODE_KEY_BYTES] = generate_random_bytes();
+    let mut file = options.open(path)?;
+    file.write_all(&private_key)?;
+    Ok(private_key)
+}
+
+/// Whether the token can no longer be used to connect; a new one has to be issued.
+pub fn is_connect_token_expired(connect_token: &ConnectToken, current_time: Duration) -> bool {
+    connect_token.expire_timestamp <= current_time.as_secs()
+}
+
+pub fn issue_connect_token(
+    current_time: Duration,
+    protocol_id: u64,
+    server_addr: SocketAddr,
+    player_user_data: &PlayerUserData,
+    private_key: &[u8; NETCODE_KEY_BYTES],
+) -> Result<ConnectToken, TokenGenerationError> {
+    ConnectToken::generate(
+        current_time,
+        protocol_id,
+        CONNECT_TOKEN_EXPIRE_SECONDS,
+        new_client_id(),
+        CLIENT_TIMEOUT_SECONDS,
+        0,
+        vec![server_addr],
+        Some(&player_user_data.to_user_data()?),
+        private_key,
+    )
+}
+
+pub fn write_connect_token(connect_token: &ConnectToken, path: impl AsRef<Path>) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    connect_token.write(&mut writer)?;
+    writer.flush()
+}
+
+pub fn read_connect_token(path: impl AsRef<Path>) -> Result<ConnectToken, NetcodeError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    ConnectToken::read(&mut reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_key_is_created_once_and_readable_only_by_its_owner() {
+        let path = std::env::temp_dir().join(format!("wz_private_key_{}.bin", new_client_id()));
+        let private_key = load_or_create_private_key(&path).unwrap();
+        assert_eq!(load_or_create_private_key(&path).unwrap(), private_key);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn connect_tokens_expire() {
+        let user_data = PlayerUserData { name: "Rick".to_string(), player_texture_type: PlayerTextureType::Rick1 };
+        let issued_at = Duration::from_secs(1_000);
+        let connect_token = issue_connect_token(issued_at, 7, "127.0.0.1:5000".parse().unwrap(), &user_data, &[0; NETCODE_KEY_BYTES]).unwrap();
+
+        assert!(!is_connect_token_expired(&connect_token, issued_at));
+        assert!(is_connect_token_expired(&connect_token, issued_at + Duration::from_secs(CONNECT_TOKEN_EXPIRE_SECONDS)));
+    }
+}
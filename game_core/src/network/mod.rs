@@ -1,2 +1,4 @@
 pub mod network;
+pub mod auth;
+pub mod snapshot;
 pub mod utils;
\ No newline at end of file
@@ -1,3 +1,8 @@
+use crate::game_mode::component::{MatchPhase, ScoreEntry, ScoreOwner, ScoreboardEntry, Team};
+use crate::game_mode::flag::FlagEvent;
+use crate::monster::component::MonsterType;
+use crate::monster::wave::WaveProgress;
+use crate::pickup::component::Pickup;
 use crate::player::component::PlayerWeaponSelected;
 use crate::player::texture::PlayerTextureType;
 use crate::weapon::fx_texture::WeaponFxTextureType;
@@ -5,13 +10,16 @@ use crate::weapon::texture::WeaponTextureType;
 use bevy::prelude::Component;
 use bevy_renet2::prelude::{ChannelConfig, ClientId, ConnectionConfig, SendType};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::time::Duration;
 
 pub const PROTOCOL_ID: u64 = 7;
+pub const SERVER_TICK_RATE: f64 = 64.0;
 
 pub enum ClientChannel {
     Input,
     Command,
+    SnapshotAck,
 }
 
 pub enum ServerChannel {
@@ -19,8 +27,21 @@ pub enum ServerChannel {
     NetworkedEntities,
 }
 
+/// Messages sent on `ClientChannel::Command`.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum PlayerCommand {
+    /// Selects the weapon in inventory slot `slot`, starting at 0.
+    SelectWeapon { slot: u8 },
+    /// Selects the owned weapon `offset` slots away from the current one.
+    CycleWeapon { offset: i8 },
+    /// Joins `team` in the team modes, refused when it would unbalance the teams.
+    ChangeTeam { team: Team },
+}
+
 #[derive(Debug, Serialize, Deserialize, Component)]
 pub enum ServerMessages {
+    /// Level to load, sent first to every joining client.
+    LoadLevel { name: String },
     PlayerCreate {
         entity: u64,
         id: ClientId,
@@ -29,30 +50,272 @@ pub enum ServerMessages {
         weapon_texture_entity_type: WeaponTextureType,
     },
     PlayerRemove { id: ClientId },
+    /// Team of a player, sent after its `PlayerCreate` and whenever it changes.
+    TeamChanged {
+        id: ClientId,
+        team: Team,
+    },
+    PlayerHit {
+        attacker: ClientId,
+        victim: ClientId,
+        damage: u32,
+        health: u32,
+    },
+    PlayerHitByMonster {
+        victim: ClientId,
+        damage: u32,
+        health: u32,
+    },
+    /// `killer` is the player whose projectile dealt the last hit, if any.
+    MonsterKilled {
+        entity: u64,
+        killer: Option<ClientId>,
+    },
+    /// Sent on every phase or score change, and to joining clients.
+    MatchState {
+        mode: String,
+        phase: MatchPhase,
+        /// Time left in the phase; `None` during a warmup still waiting for players.
+        remaining_secs: Option<f32>,
+        score_limit: u32,
+        scores: Vec<ScoreEntry>,
+        /// Set once the match ended, `None` on a draw.
+        winner: Option<ScoreOwner>,
+    },
+    /// Survival mode state, sent when the phase or the monster count changes.
+    WaveStatus {
+        wave: u32,
+        progress: WaveProgress,
+    },
+    /// Capture-the-flag transition of the flag of `team`.
+    FlagChanged {
+        team: Team,
+        event: FlagEvent,
+    },
+    PickupCollected {
+        id: ClientId,
+        pickup: Pickup,
+    },
+    /// Grenade detonation; damage and knockback are applied by the server, this is for effects.
+    Explosion {
+        owner: ClientId,
+        translation: [f32; 3],
+        radius: f32,
+    },
+    /// `killer` is `None` when nobody hit the victim since it last spawned, `weapon` is what the killer hit it with.
+    PlayerDied {
+        victim: ClientId,
+        killer: Option<ClientId>,
+        weapon: Option<WeaponTextureType>,
+        assists: Vec<ClientId>,
+        respawn_in_secs: f32,
+    },
+    /// Every connected player with its statistics, sent when they change.
+    Scoreboard {
+        players: Vec<ScoreboardEntry>,
+    },
+    PlayerRespawned {
+        id: ClientId,
+        translation: [f32; 3],
+        invulnerable_for_secs: f32,
+    },
 }
 
+/// Envelope for every message sent on `ServerChannel::ServerMessages`,
+/// stamped with the server tick it was produced on.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TickedServerMessage {
+    pub tick: u64,
+    pub message: ServerMessages,
+}
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct NetworkedEntities {
-    pub entities: Vec<u64>,
-    pub translations: Vec<[f32; 3]>,
-    pub sprite_index: Vec<usize>,
-    pub sprite_flip_x: Vec<bool>,
-    pub player_texture_entity_type: Vec<PlayerTextureType>,
-    pub weapon_texture_entity_type: Vec<PlayerWeaponSelected>,
-    pub player_aim_direction: Vec<f32>,
-    pub projectile_entities: Vec<u64>,
-    pub projectile_translations: Vec<[f32; 3]>,
-    pub projectile_sprite_index: Vec<usize>,
-    pub projectile_sprite_flip_y: Vec<bool>,
+    pub tick: u64,
+    pub entities: Vec<NetworkedEntity>,
+}
+
+/// One replicated entity, tagged with its kind.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum NetworkedEntity {
+    Player(NetworkedPlayer),
+    Projectile(NetworkedProjectile),
+    PelletVolley(NetworkedPelletVolley),
+    Pickup(NetworkedPickup),
+    Monster(NetworkedMonster),
+    Flag(NetworkedFlag),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NetworkedPlayer {
+    pub entity: u64,
+    pub translation: [f32; 3],
+    pub sprite_index: usize,
+    pub sprite_flip_x: bool,
+    pub player_texture_entity_type: PlayerTextureType,
+    pub weapon_texture_entity_type: PlayerWeaponSelected,
+    pub aim_direction: f32,
+    pub velocity: [f32; 2],
+    pub grounded: bool,
+    pub jumps_left: u8,
+    pub last_input_sequence: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NetworkedProjectile {
+    pub entity: u64,
+    pub owner: ClientId,
+    pub translation: [f32; 3],
+    pub aim_direction: f32,
+    pub sprite_index: usize,
+    pub sprite_flip_y: bool,
+    pub weapon_fx_texture_type: WeaponFxTextureType,
+}
+
+/// Multi-pellet shot; only changes, and so only gets resent, when a pellet disappears.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NetworkedPelletVolley {
+    pub entity: u64,
+    pub owner: ClientId,
+    pub origin: [f32; 3],
+    pub aim_direction: f32,
+    pub spread_degrees: f32,
+    pub seed: u64,
+    pub pellet_count: u8,
+    pub speed: f32,
+    pub fired_tick: u64,
+    /// Bit `i` is set while pellet `i` is still flying.
+    pub alive_pellets: u32,
     pub weapon_fx_texture_type: WeaponFxTextureType,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NetworkedPickup {
+    pub entity: u64,
+    pub translation: [f32; 3],
+    pub pickup: Pickup,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NetworkedMonster {
+    pub entity: u64,
+    pub translation: [f32; 3],
+    pub sprite_index: usize,
+    pub sprite_flip_x: bool,
+    pub monster_type: MonsterType,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NetworkedFlag {
+    pub entity: u64,
+    pub translation: [f32; 3],
+    pub team: Team,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SnapshotError {
+    MissingBaseline(u64),
+    IncompletePlayer(u64),
+    DuplicateEntity(u64),
+    NonFiniteValue(u64),
+}
+
+impl NetworkedEntity {
+    pub fn entity(&self) -> u64 {
+        match self {
+            NetworkedEntity::Player(player) => player.entity,
+            NetworkedEntity::Projectile(projectile) => projectile.entity,
+            NetworkedEntity::PelletVolley(volley) => volley.entity,
+            NetworkedEntity::Pickup(pickup) => pickup.entity,
+            NetworkedEntity::Monster(monster) => monster.entity,
+            NetworkedEntity::Flag(flag) => flag.entity,
+        }
+    }
+
+    fn is_finite(&self) -> bool {
+        let translation_is_finite = |translation: &[f32; 3]| translation.iter().all(|value| value.is_finite());
+        match self {
+            NetworkedEntity::Player(player) => translation_is_finite(&player.translation)
+                && player.aim_direction.is_finite()
+                && player.velocity.iter().all(|value| value.is_finite()),
+            NetworkedEntity::Projectile(projectile) => translation_is_finite(&projectile.translation)
+                && projectile.aim_direction.is_finite(),
+            NetworkedEntity::PelletVolley(volley) => translation_is_finite(&volley.origin)
+                && volley.aim_direction.is_finite()
+                && volley.spread_degrees.is_finite()
+                && volley.speed.is_finite(),
+            NetworkedEntity::Pickup(pickup) => translation_is_finite(&pickup.translation),
+            NetworkedEntity::Monster(monster) => translation_is_finite(&monster.translation),
+            NetworkedEntity::Flag(flag) => translation_is_finite(&flag.translation),
+        }
+    }
+}
+
+impl NetworkedEntities {
+    /// Rejects snapshots that would put the client in an inconsistent state.
+    pub fn validate(&self) -> Result<(), SnapshotError> {
+        let mut seen = HashSet::new();
+        for networked_entity in &self.entities {
+            let entity = networked_entity.entity();
+            if !seen.insert(entity) {
+                return Err(SnapshotError::DuplicateEntity(entity));
+            }
+            if !networked_entity.is_finite() {
+                return Err(SnapshotError::NonFiniteValue(entity));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn players(&self) -> impl Iterator<Item = &NetworkedPlayer> {
+        self.entities.iter().filter_map(|networked_entity| match networked_entity {
+            NetworkedEntity::Player(player) => Some(player),
+            _ => None,
+        })
+    }
+
+    pub fn projectiles(&self) -> impl Iterator<Item = &NetworkedProjectile> {
+        self.entities.iter().filter_map(|networked_entity| match networked_entity {
+            NetworkedEntity::Projectile(projectile) => Some(projectile),
+            _ => None,
+        })
+    }
+
+    pub fn pellet_volleys(&self) -> impl Iterator<Item = &NetworkedPelletVolley> {
+        self.entities.iter().filter_map(|networked_entity| match networked_entity {
+            NetworkedEntity::PelletVolley(volley) => Some(volley),
+            _ => None,
+        })
+    }
+
+    pub fn monsters(&self) -> impl Iterator<Item = &NetworkedMonster> {
+        self.entities.iter().filter_map(|networked_entity| match networked_entity {
+            NetworkedEntity::Monster(monster) => Some(monster),
+            _ => None,
+        })
+    }
+
+    pub fn flags(&self) -> impl Iterator<Item = &NetworkedFlag> {
+        self.entities.iter().filter_map(|networked_entity| match networked_entity {
+            NetworkedEntity::Flag(flag) => Some(flag),
+            _ => None,
+        })
+    }
+
+    pub fn pickups(&self) -> impl Iterator<Item = &NetworkedPickup> {
+        self.entities.iter().filter_map(|networked_entity| match networked_entity {
+            NetworkedEntity::Pickup(pickup) => Some(pickup),
+            _ => None,
+        })
+    }
+}
+
 impl From<ClientChannel> for u8 {
     fn from(channel_id: ClientChannel) -> Self {
         match channel_id {
             ClientChannel::Command => 0,
             ClientChannel::Input => 1,
+            ClientChannel::SnapshotAck => 2,
         }
     }
 }
@@ -74,6 +337,11 @@ impl ClientChannel {
                     resend_time: Duration::ZERO,
                 },
             },
+            ChannelConfig {
+                channel_id: Self::SnapshotAck.into(),
+                max_memory_usage_bytes: 1024 * 1024,
+                send_type: SendType::Unreliable,
+            },
         ]
     }
 }
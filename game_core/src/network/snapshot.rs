@@ -0,0 +1,361 @@
+use crate::network::network::{NetworkedEntities, NetworkedEntity, NetworkedPlayer, SnapshotError};
+use crate::player::component::PlayerWeaponSelected;
+use crate::player::texture::PlayerTextureType;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Player positions are sent as multiples of this many pixels.
+pub const POSITION_QUANTUM: f32 = 0.125;
+const SNAPSHOT_HISTORY_LEN: usize = 64;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotAck {
+    pub tick: u64,
+}
+
+/// Fields of a player that changed since the baseline; `None` means unchanged.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PlayerDelta {
+    pub entity: u64,
+    pub translation: Option<[i16; 2]>,
+    pub sprite_index: Option<usize>,
+    pub sprite_flip_x: Option<bool>,
+    pub player_texture_entity_type: Option<PlayerTextureType>,
+    pub weapon_texture_entity_type: Option<PlayerWeaponSelected>,
+    pub aim_direction: Option<f32>,
+    pub velocity: Option<[f32; 2]>,
+    pub grounded: Option<bool>,
+    pub jumps_left: Option<u8>,
+    pub last_input_sequence: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum EntityDelta {
+    Player(PlayerDelta),
+    Full(NetworkedEntity),
+}
+
+/// Wire format of `ServerChannel::NetworkedEntities`: a snapshot encoded
+/// against the last snapshot the receiving client acknowledged.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+    pub tick: u64,
+    pub baseline_tick: Option<u64>,
+    pub changed: Vec<EntityDelta>,
+    pub removed: Vec<u64>,
+}
+
+/// Recently sent (server) or decoded (client) snapshots, used as delta baselines.
+#[derive(Resource, Default)]
+pub struct SnapshotHistory(VecDeque<NetworkedEntities>);
+
+impl SnapshotHistory {
+    pub fn push(&mut self, snapshot: NetworkedEntities) {
+        self.0.push_back(snapshot);
+        while self.0.len() > SNAPSHOT_HISTORY_LEN {
+            self.0.pop_front();
+        }
+    }
+
+    pub fn get(&self, tick: u64) -> Option<&NetworkedEntities> {
+        self.0.iter().find(|snapshot| snapshot.tick == tick)
+    }
+}
+
+/// `None` past ±4096 pixels, where players are sent with their full translation instead.
+pub fn quantize_position(value: f32) -> Option<i16> {
+    let quantized = (value / POSITION_QUANTUM).round();
+    (quantized >= i16::MIN as f32 && quantized <= i16::MAX as f32).then_some(quantized as i16)
+}
+
+pub fn dequantize_position(value: i16) -> f32 {
+    value as f32 * POSITION_QUANTUM
+}
+
+fn quantize_translation(translation: &[f32; 3]) -> Option<[i16; 2]> {
+    Some([quantize_position(translation[0])?, quantize_position(translation[1])?])
+}
+
+fn changed<T: PartialEq + Clone>(baseline: &T, current: &T) -> Option<T> {
+    (baseline != current).then(|| current.clone())
+}
+
+impl PlayerDelta {
+    /// `None` when nothing changed; `current` must have a quantizable translation.
+    fn between(baseline: &NetworkedPlayer, current: &NetworkedPlayer) -> Option<Self> {
+        let delta = Self {
+            entity: current.entity,
+            translation: changed(&quantize_translation(&baseline.translation), &quantize_translation(&current.translation)).flatten(),
+            sprite_index: changed(&baseline.sprite_index, &current.sprite_index),
+            sprite_flip_x: changed(&baseline.sprite_flip_x, &current.sprite_flip_x),
+            player_texture_entity_type: changed(&baseline.player_texture_entity_type, &current.player_texture_entity_type),
+            weapon_texture_entity_type: changed(&baseline.weapon_texture_entity_type, &current.weapon_texture_entity_type),
+            aim_direction: changed(&baseline.aim_direction, &current.aim_direction),
+            velocity: changed(&baseline.velocity, &current.velocity),
+            grounded: changed(&baseline.grounded, &current.grounded),
+            jumps_left: changed(&baseline.jumps_left, &current.jumps_left),
+            last_input_sequence: changed(&baseline.last_input_sequence, &current.last_input_sequence),
+        };
+        delta.has_changes().then_some(delta)
+    }
+
+    fn has_changes(&self) -> bool {
+        self.translation.is_some()
+            || self.sprite_index.is_some()
+            || self.sprite_flip_x.is_some()
+            || self.player_texture_entity_type.is_some()
+            || self.weapon_texture_entity_type.is_some()
+            || self.aim_direction.is_some()
+            || self.velocity.is_some()
+            || self.grounded.is_some()
+            || self.jumps_left.is_some()
+            || self.last_input_sequence.is_some()
+    }
+
+    fn apply(self, baseline: &NetworkedPlayer) -> NetworkedPlayer {
+        NetworkedPlayer {
+            entity: self.entity,
+            translation: self.translation
+                .map(|[x, y]| [dequantize_position(x), dequantize_position(y), baseline.translation[2]])
+                .unwrap_or(baseline.translation),
+            sprite_index: self.sprite_index.unwrap_or(baseline.sprite_index),
+            sprite_flip_x: self.sprite_flip_x.unwrap_or(baseline.sprite_flip_x),
+            player_texture_entity_type: self.player_texture_entity_type
+                .unwrap_or_else(|| baseline.player_texture_entity_type.clone()),
+            weapon_texture_entity_type: self.weapon_texture_entity_type
+                .unwrap_or_else(|| baseline.weapon_texture_entity_type.clone()),
+            aim_direction: self.aim_direction.unwrap_or(baseline.aim_direction),
+            velocity: self.velocity.unwrap_or(baseline.velocity),
+            grounded: self.grounded.unwrap_or(baseline.grounded),
+            jumps_left: self.jumps_left.unwrap_or(baseline.jumps_left),
+            last_input_sequence: self.last_input_sequence.unwrap_or(baseline.last_input_sequence),
+        }
+    }
+}
+
+pub fn encode_snapshot(current: &NetworkedEntities, baseline: Option<&NetworkedEntities>) -> SnapshotDelta {
+    let baseline_entities: HashMap<u64, &NetworkedEntity> = baseline
+        .map(|baseline| baseline.entities.iter().map(|networked_entity| (networked_entity.entity(), networked_entity)).collect())
+        .unwrap_or_default();
+    let current_entities: HashSet<u64> = current.entities.iter().map(NetworkedEntity::entity).collect();
+
+    let changed = current.entities
+        .iter()
+        .filter_map(|networked_entity| match (networked_entity, baseline_entities.get(&networked_entity.entity())) {
+            (NetworkedEntity::Player(player), Some(NetworkedEntity::Player(baseline_player)))
+                if quantize_translation(&player.translation).is_some() =>
+            {
+                PlayerDelta::between(baseline_player, player).map(EntityDelta::Player)
+            }
+            (_, Some(baseline_entity)) if *baseline_entity == networked_entity => None,
+            _ => Some(EntityDelta::Full(networked_entity.clone())),
+        })
+        .collect();
+
+    SnapshotDelta {
+        tick: current.tick,
+        baseline_tick: baseline.map(|baseline| baseline.tick),
+        changed,
+        removed: baseline_entities
+            .keys()
+            .filter(|entity| !current_entities.contains(entity))
+            .copied()
+            .collect(),
+    }
+}
+
+pub fn decode_snapshot(
+    delta: SnapshotDelta,
+    baseline: Option<&NetworkedEntities>,
+) -> Result<NetworkedEntities, SnapshotError> {
+    let mut entities: Vec<NetworkedEntity> = match (delta.baseline_tick, baseline) {
+        (None, _) => Vec::new(),
+        (Some(tick), Some(baseline)) if baseline.tick == tick => baseline.entities
+            .iter()
+            .filter(|networked_entity| !delta.removed.contains(&networked_entity.entity()))
+            .cloned()
+            .collect(),
+        (Some(tick), _) => return Err(SnapshotError::MissingBaseline(tick)),
+    };
+
+    for entity_delta in delta.changed {
+        let entity = match &entity_delta {
+            EntityDelta::Player(player_delta) => player_delta.entity,
+            EntityDelta::Full(networked_entity) => networked_entity.entity(),
+        };
+        let position = entities.iter().position(|networked_entity| networked_entity.entity() == entity);
+
+        let networked_entity = match (entity_delta, position.map(|index| &entities[index])) {
+            (EntityDelta::Full(networked_entity), _) => networked_entity,
+            (EntityDelta::Player(player_delta), Some(NetworkedEntity::Player(baseline_player))) => {
+                NetworkedEntity::Player(player_delta.apply(baseline_player))
+            }
+            (EntityDelta::Player(_), _) => return Err(SnapshotError::IncompletePlayer(entity)),
+        };
+
+        match position {
+            Some(index) => entities[index] = networked_entity,
+            None => entities.push(networked_entity),
+        }
+    }
+
+    let networked_entities = NetworkedEntities { tick: delta.tick, entities };
+    networked_entities.validate()?;
+    Ok(networked_entities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::network::{NetworkedPelletVolley, NetworkedProjectile};
+    use crate::weapon::fx_texture::WeaponFxTextureType;
+    use crate::weapon::texture::WeaponTextureType;
+
+    fn player(entity: u64, translation: [f32; 3]) -> NetworkedPlayer {
+        NetworkedPlayer {
+            entity,
+            translation,
+            sprite_index: 0,
+            sprite_flip_x: false,
+            player_texture_entity_type: PlayerTextureType::Rick1,
+            weapon_texture_entity_type: PlayerWeaponSelected { weapon_texture_type: WeaponTextureType::Pistol },
+            aim_direction: 0.0,
+            velocity: [0.0, 0.0],
+            grounded: true,
+            jumps_left: 2,
+            last_input_sequence: 1,
+        }
+    }
+
+    fn projectile(entity: u64, owner: u64, aim_direction: f32, weapon_fx_texture_type: WeaponFxTextureType) -> NetworkedProjectile {
+        NetworkedProjectile {
+            entity,
+            owner,
+            translation: [entity as f32, 0.0, 0.0],
+            aim_direction,
+            sprite_index: 0,
+            sprite_flip_y: false,
+            weapon_fx_texture_type,
+        }
+    }
+
+    #[test]
+    fn mixed_weapon_projectiles_keep_their_own_type() {
+        let pistol_shot = projectile(1, 10, 0.5, WeaponFxTextureType::Pistol);
+        let grenade = projectile(2, 20, 2.5, WeaponFxTextureType::GrenadeLauncher);
+        let snapshot = NetworkedEntities {
+            tick: 1,
+            entities: vec![
+                NetworkedEntity::Projectile(pistol_shot.clone()),
+                NetworkedEntity::Projectile(grenade.clone()),
+            ],
+        };
+
+        let decoded = decode_snapshot(encode_snapshot(&snapshot, None), None).unwrap();
+
+        assert_eq!(decoded.projectiles().cloned().collect::<Vec<_>>(), vec![pistol_shot, grenade]);
+    }
+
+    #[test]
+    fn malformed_snapshots_are_rejected() {
+        let mut broken_projectile = projectile(1, 10, 0.5, WeaponFxTextureType::Pistol);
+        broken_projectile.translation[0] = f32::NAN;
+        let non_finite = SnapshotDelta {
+            tick: 1,
+            changed: vec![EntityDelta::Full(NetworkedEntity::Projectile(broken_projectile))],
+            ..Default::default()
+        };
+        let orphan_player = SnapshotDelta {
+            tick: 2,
+            changed: vec![EntityDelta::Player(PlayerDelta { entity: 3, ..Default::default() })],
+            ..Default::default()
+        };
+
+        assert_eq!(decode_snapshot(non_finite, None).err(), Some(SnapshotError::NonFiniteValue(1)));
+        assert_eq!(decode_snapshot(orphan_player, None).err(), Some(SnapshotError::IncompletePlayer(3)));
+    }
+
+    #[test]
+    fn pellet_volley_is_only_resent_when_a_pellet_disappears() {
+        let volley = NetworkedPelletVolley {
+            entity: 1,
+            owner: 10,
+            origin: [0.0, 0.0, 0.0],
+            aim_direction: 0.5,
+            spread_degrees: 20.0,
+            seed: 42,
+            pellet_count: 6,
+            speed: 900.0,
+            fired_tick: 1,
+            alive_pellets: 0b11_1111,
+            weapon_fx_texture_type: WeaponFxTextureType::Shotgun,
+        };
+        let snapshot_at = |tick, alive_pellets| NetworkedEntities {
+            tick,
+            entities: vec![NetworkedEntity::PelletVolley(NetworkedPelletVolley { alive_pellets, ..volley.clone() })],
+        };
+        let baseline = snapshot_at(1, 0b11_1111);
+
+        assert!(encode_snapshot(&snapshot_at(2, 0b11_1111), Some(&baseline)).changed.is_empty());
+        assert_eq!(encode_snapshot(&snapshot_at(3, 0b11_0111), Some(&baseline)).changed.len(), 1);
+    }
+
+    #[test]
+    fn delta_against_an_acked_baseline_decodes_to_the_full_snapshot() {
+        let baseline = NetworkedEntities {
+            tick: 10,
+            entities: vec![
+                NetworkedEntity::Player(player(1, [100.0, 50.0, 1.0])),
+                NetworkedEntity::Player(player(2, [-300.0, 0.0, 1.0])),
+                NetworkedEntity::Projectile(projectile(3, 1, 0.0, WeaponFxTextureType::Pistol)),
+            ],
+        };
+        let moved = NetworkedPlayer { translation: [112.5, 48.25, 1.0], aim_direction: 1.5, last_input_sequence: 4, ..player(1, [0.0; 3]) };
+        let current = NetworkedEntities {
+            tick: 12,
+            entities: vec![
+                NetworkedEntity::Player(moved),
+                NetworkedEntity::Player(player(2, [-300.0, 0.0, 1.0])),
+                NetworkedEntity::Projectile(projectile(4, 2, 3.0, WeaponFxTextureType::Rifle)),
+            ],
+        };
+
+        let delta = encode_snapshot(&current, Some(&baseline));
+        assert_eq!(delta.baseline_tick, Some(10));
+        assert_eq!(delta.removed, vec![3]);
+        assert_eq!(delta.changed.len(), 2);
+        assert!(matches!(&delta.changed[0], EntityDelta::Player(PlayerDelta { entity: 1, sprite_index: None, .. })));
+
+        let decoded = decode_snapshot(delta, Some(&baseline)).unwrap();
+        assert_eq!(decoded.tick, 12);
+        assert_eq!(decoded.entities, current.entities);
+    }
+
+    #[test]
+    fn missing_baseline_needs_a_full_snapshot() {
+        let current = NetworkedEntities { tick: 5, entities: vec![NetworkedEntity::Player(player(1, [10.0, 20.0, 1.0]))] };
+
+        let full = encode_snapshot(&current, None);
+        assert_eq!(full.baseline_tick, None);
+        assert!(matches!(full.changed[..], [EntityDelta::Full(_)]));
+        assert_eq!(decode_snapshot(full, None).unwrap().entities, current.entities);
+
+        let baseline = NetworkedEntities { tick: 3, ..current.clone() };
+        let unknown_baseline = || SnapshotDelta { tick: 6, baseline_tick: Some(4), ..Default::default() };
+        assert_eq!(decode_snapshot(unknown_baseline(), None).err(), Some(SnapshotError::MissingBaseline(4)));
+        assert_eq!(decode_snapshot(unknown_baseline(), Some(&baseline)).err(), Some(SnapshotError::MissingBaseline(4)));
+    }
+
+    #[test]
+    fn players_beyond_the_quantized_range_are_sent_in_full() {
+        let baseline = NetworkedEntities { tick: 1, entities: vec![NetworkedEntity::Player(player(1, [4000.0, 0.0, 1.0]))] };
+        let current = NetworkedEntities { tick: 2, entities: vec![NetworkedEntity::Player(player(1, [5000.3, -6000.0, 1.0]))] };
+
+        let delta = encode_snapshot(&current, Some(&baseline));
+        assert!(matches!(delta.changed[..], [EntityDelta::Full(_)]));
+        assert_eq!(decode_snapshot(delta, Some(&baseline)).unwrap().entities, current.entities);
+        assert_eq!(quantize_position(5000.0), None);
+        assert_eq!(quantize_position(-4096.0), Some(i16::MIN));
+    }
+}
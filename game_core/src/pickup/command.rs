@@ -0,0 +1,50 @@
+use crate::pickup::component::{Pickup, PickupRespawn, PickupSpawn};
+use crate::texture::component::SpriteFrame;
+use crate::texture::entity::TextureHandleMap;
+use crate::texture::system::sprite_from_frame;
+use crate::weapon::command::weapon_sprite;
+use crate::weapon::texture::{WeaponTextureType, WeaponTextures, WEAPON_LAYOUT_SIZE};
+use bevy::asset::Assets;
+use bevy::image::TextureAtlasLayout;
+use bevy::math::{URect, UVec2};
+use bevy::prelude::{Commands, Entity, GlobalTransform, Name, Res, ResMut, Sprite, Transform};
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, Sensor};
+
+/// Crate lettered H in the weapons sheet.
+const HEALTH_CRATE_MIN: UVec2 = UVec2::new(170, 28);
+/// Crate lettered C in the weapons sheet.
+const AMMO_CRATE_MIN: UVec2 = UVec2::new(397, 344);
+const CRATE_SIZE: UVec2 = UVec2::new(51, 46);
+
+pub fn spawn_pickup(commands: &mut Commands, pickup_spawn: PickupSpawn) -> Entity {
+    commands.spawn((
+        Name::new("Pickup"),
+        pickup_spawn.pickup,
+        PickupRespawn(pickup_spawn.respawn_secs),
+        Transform::from_translation(pickup_spawn.position.extend(0.0)),
+        GlobalTransform::default(),
+        Sensor,
+        Collider::cuboid(25.0, 20.0),
+        ActiveEvents::COLLISION_EVENTS,
+    )).id()
+}
+
+/// Weapons are drawn with their own sprite, other pickups as crates from the same sheet.
+pub fn pickup_sprite(
+    pickup: &Pickup,
+    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
+    weapon_textures: &Res<WeaponTextures>,
+) -> Sprite {
+    let crate_min = match pickup {
+        Pickup::Weapon(weapon_texture_type) => {
+            return weapon_sprite(weapon_texture_type, SpriteFrame::default(), texture_atlas_layouts, weapon_textures);
+        }
+        Pickup::Ammo => AMMO_CRATE_MIN,
+        Pickup::Health(_) => HEALTH_CRATE_MIN,
+    };
+
+    let mut layout = TextureAtlasLayout::new_empty(WEAPON_LAYOUT_SIZE);
+    layout.add_texture(URect::from_corners(crate_min, crate_min + CRATE_SIZE));
+    let image = weapon_textures.get_handle(WeaponTextureType::Pistol).unwrap_or_default();
+    sprite_from_frame(image, texture_atlas_layouts.add(layout), SpriteFrame::default())
+}
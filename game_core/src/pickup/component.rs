@@ -0,0 +1,37 @@
+use crate::weapon::texture::WeaponTextureType;
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Timer, TimerMode};
+use serde::{Deserialize, Serialize};
+
+/// Item lying in the level, collected by the first player touching it.
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Pickup {
+    /// Adds the weapon to the inventory, or restocks its reserve when already owned.
+    Weapon(WeaponTextureType),
+    /// Restocks the reserve of every owned weapon, only when one is missing rounds.
+    Ammo,
+    /// Restores this much health, only to wounded players.
+    Health(u32),
+}
+
+/// Level placement of a pickup.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PickupSpawn {
+    pub pickup: Pickup,
+    pub position: Vec2,
+    pub respawn_secs: f32,
+}
+
+/// Time a pickup stays away once collected.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PickupRespawn(pub f32);
+
+/// Collected pickup, hidden and intangible until the timer ends.
+#[derive(Component, Debug)]
+pub struct PickupCooldown(pub Timer);
+
+impl PickupCooldown {
+    pub fn new(respawn: &PickupRespawn) -> Self {
+        Self(Timer::from_seconds(respawn.0, TimerMode::Once))
+    }
+}
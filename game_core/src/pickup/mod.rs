@@ -0,0 +1,2 @@
+pub mod component;
+pub mod command;
@@ -1,8 +1,9 @@
-use crate::player::component::{AnimationTimer, Grounded, JumpCounter, Player, PlayerChildren, PlayerInput, PlayerNetwork, PlayerWeaponSelected};
+use crate::player::component::{AnimationTimer, Grounded, Health, JumpCounter, Player, PlayerChildren, PlayerInput, PlayerNetwork, PlayerWeaponSelected};
 use crate::player::texture::{PlayerTextureEntity, PlayerTextureType, PlayerTextures};
-use crate::texture::system::handle_from_texture;
+use crate::texture::component::SpriteFrame;
+use crate::texture::system::{handle_from_texture, sprite_from_frame};
 use bevy::asset::{Assets, Handle};
-use bevy::image::{Image, TextureAtlas, TextureAtlasLayout};
+use bevy::image::{Image, TextureAtlasLayout};
 use bevy::math::Vec3;
 use bevy::prelude::{Commands, Entity, GlobalTransform, Name, Res, ResMut, Sprite, Timer, TimerMode, Transform};
 use bevy_renet2::prelude::ClientId;
@@ -16,8 +17,6 @@ pub struct SpawnPlayerParams<'a> {
 }
 pub fn spawn_player_entity(
     commands: &mut Commands,
-    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
-    player_textures: &mut Res<PlayerTextures>,
     SpawnPlayerParams {
         pivot,
         weapon,
@@ -28,26 +27,19 @@ pub fn spawn_player_entity(
     }: SpawnPlayerParams,
 ) -> Entity {
     let rick_texture = PlayerTextureEntity::new(player_texture_type);
-    let (image, layout) =
-        handle_from_player_texture(
-            &rick_texture.player_texture_type,
-            &mut *texture_atlas_layouts, player_textures,
-        );
 
     commands.spawn((
         Name::new("Player"),
-        Sprite::from_atlas_image(
-            image,
-            TextureAtlas {
-                layout,
-                index: rick_texture.animation_indices.first as usize,
-            },
-        ),
+        SpriteFrame {
+            index: rick_texture.animation_indices.first as usize,
+            ..Default::default()
+        },
         rick_texture.animation_indices,
         AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
         Player { speed: 350. },
         PlayerInput::default(),
         Grounded(false),
+        Health::default(),
         JumpCounter { jumps_left: 2, max_jumps: 2 },
         Transform::from_translation(position).with_scale(Vec3::splat(0.5)),
         GlobalTransform::default(),
@@ -56,6 +48,15 @@ pub fn spawn_player_entity(
         PlayerChildren { pivot, weapon, sensor }
     )).insert(player_texture_type.clone()).id()
 }
+pub fn player_sprite(
+    player_texture_type: &PlayerTextureType,
+    sprite_frame: SpriteFrame,
+    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
+    player_textures: &Res<PlayerTextures>,
+) -> Sprite {
+    let (image, layout) = handle_from_player_texture(player_texture_type, texture_atlas_layouts, player_textures);
+    sprite_from_frame(image, layout, sprite_frame)
+}
 pub fn handle_from_player_texture(
     texture_entity_type: &PlayerTextureType,
     texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
@@ -1,5 +1,7 @@
+use crate::weapon::definition::WeaponDefinitions;
+use crate::weapon::state::WeaponState;
 use crate::weapon::texture::WeaponTextureType;
-use bevy::prelude::{Bundle, Commands, Component, Deref, Entity, Name, Resource, Timer, Transform, Vec2};
+use bevy::prelude::{Bundle, Commands, Component, Deref, Entity, Name, Resource, Timer, TimerMode, Transform, Vec2};
 use bevy_rapier2d::dynamics::{GravityScale, LockedAxes, RigidBody, Velocity};
 use bevy_rapier2d::geometry::{Collider, Friction};
 use bevy_rapier2d::prelude::{ActiveEvents, Sensor};
@@ -14,6 +16,9 @@ pub struct PlayerNetwork {
 #[derive(Debug, Resource)]
 pub struct CurrentClientId(pub u64);
 
+#[derive(Debug, Component, Clone)]
+pub struct PlayerName(pub String);
+
 #[derive(Component)]
 pub struct ControlledPlayer;
 
@@ -37,7 +42,10 @@ pub struct PlayerInput {
     pub right: bool,
     pub jump: bool,
     pub aim_direction: f32,
+    /// Trigger held down; the server decides when the weapon actually fires.
     pub shoot: bool,
+    pub reload: bool,
+    pub sequence: u32,
 }
 
 #[derive(Resource, Default)]
@@ -77,6 +85,77 @@ pub struct AnimationTimer(pub Timer);
 #[derive(Component, Default)]
 pub struct Grounded(pub bool);
 
+pub const PLAYER_MAX_HEALTH: u32 = 100;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            current: PLAYER_MAX_HEALTH,
+            max: PLAYER_MAX_HEALTH,
+        }
+    }
+}
+
+impl Health {
+    /// Removes up to `amount` health and returns how much was actually taken.
+    pub fn take_damage(&mut self, amount: u32) -> u32 {
+        let taken = amount.min(self.current);
+        self.current -= taken;
+        taken
+    }
+
+    /// Restores up to `amount` health and returns how much was actually restored.
+    pub fn heal(&mut self, amount: u32) -> u32 {
+        let restored = amount.min(self.max - self.current);
+        self.current += restored;
+        restored
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+}
+
+pub const RESPAWN_DELAY_SECS: f32 = 3.0;
+pub const SPAWN_INVULNERABILITY_SECS: f32 = 2.0;
+
+/// Player waiting to respawn; its input is ignored until then.
+#[derive(Component, Debug)]
+pub struct Dead {
+    pub respawn_timer: Timer,
+}
+
+impl Dead {
+    pub fn new(respawn_delay_secs: f32) -> Self {
+        Self {
+            respawn_timer: Timer::from_seconds(respawn_delay_secs, TimerMode::Once),
+        }
+    }
+}
+
+/// Player that takes no damage until the timer ends, given right after respawning.
+#[derive(Component, Debug)]
+pub struct Invulnerable(pub Timer);
+
+impl Invulnerable {
+    pub fn new(duration_secs: f32) -> Self {
+        Self(Timer::from_seconds(duration_secs, TimerMode::Once))
+    }
+}
+
+/// Last player that damaged this one and its weapon, credited with the kill.
+#[derive(Component, Debug, Clone)]
+pub struct LastAttacker {
+    pub id: ClientId,
+    pub weapon: WeaponTextureType,
+}
+
 #[derive(Bundle)]
 pub struct SensorBundle {
     pub sensor: Sensor,
@@ -92,7 +171,7 @@ impl Default for SensorBundle {
     }
 }
 
-#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+#[derive(Component, Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct PlayerWeaponSelected {
     pub weapon_texture_type: WeaponTextureType,
 }
@@ -105,6 +184,76 @@ impl PlayerWeaponSelected {
     }
 }
 
+/// Weapons a player owns, in slot order, each keeping its magazine and reload while holstered.
+#[derive(Component, Debug, Clone)]
+pub struct WeaponInventory(pub Vec<OwnedWeapon>);
+
+#[derive(Debug, Clone)]
+pub struct OwnedWeapon {
+    pub weapon: WeaponTextureType,
+    pub state: WeaponState,
+}
+
+impl WeaponInventory {
+    /// Every weapon loaded; `definitions` list every weapon type once validated.
+    pub fn new(weapons: &[WeaponTextureType], definitions: &WeaponDefinitions) -> Self {
+        let mut inventory = Self(Vec::new());
+        for weapon in weapons {
+            inventory.add(weapon.clone(), definitions);
+        }
+        inventory
+    }
+
+    pub fn slot(&self, slot: usize) -> Option<&WeaponTextureType> {
+        self.0.get(slot).map(|owned| &owned.weapon)
+    }
+
+    /// Weapon `offset` slots away from `current`, wrapping around.
+    pub fn cycle(&self, current: &WeaponTextureType, offset: i8) -> Option<&WeaponTextureType> {
+        let current_slot = self.0.iter().position(|owned| owned.weapon == *current).unwrap_or(0) as i64;
+        let slot = (current_slot + offset as i64).rem_euclid(self.0.len().max(1) as i64);
+        self.slot(slot as usize)
+    }
+
+    /// Adds `weapon` loaded unless already owned; returns whether it was added.
+    pub fn add(&mut self, weapon: WeaponTextureType, definitions: &WeaponDefinitions) -> bool {
+        if self.state(&weapon).is_some() {
+            return false;
+        }
+        let Some(definition) = definitions.get(&weapon) else { return false };
+        self.0.push(OwnedWeapon { weapon, state: WeaponState::new(definition) });
+        true
+    }
+
+    pub fn state(&self, weapon: &WeaponTextureType) -> Option<&WeaponState> {
+        self.0.iter().find(|owned| owned.weapon == *weapon).map(|owned| &owned.state)
+    }
+
+    pub fn state_mut(&mut self, weapon: &WeaponTextureType) -> Option<&mut WeaponState> {
+        self.0.iter_mut().find(|owned| owned.weapon == *weapon).map(|owned| &mut owned.state)
+    }
+
+    /// Fills the reserve of every weapon, or of `weapon` only; returns whether any round was added.
+    pub fn restock(&mut self, weapon: Option<&WeaponTextureType>, definitions: &WeaponDefinitions) -> bool {
+        let mut restocked = false;
+        for owned in self.0.iter_mut().filter(|owned| weapon.is_none_or(|weapon| owned.weapon == *weapon)) {
+            if let Some(definition) = definitions.get(&owned.weapon) {
+                restocked |= owned.state.restock(definition);
+            }
+        }
+        restocked
+    }
+
+    /// Reloads every weapon at once, e.g. on respawn.
+    pub fn refill(&mut self, definitions: &WeaponDefinitions) {
+        for owned in &mut self.0 {
+            if let Some(definition) = definitions.get(&owned.weapon) {
+                owned.state = WeaponState::new(definition);
+            }
+        }
+    }
+}
+
 pub fn player_physics() -> (
     RigidBody,
     LockedAxes,
@@ -1,5 +1,5 @@
 use crate::player::component::{Grounded, JumpCounter, Player, PlayerInput};
-use bevy::math::Vec2;
+use bevy::math::{Vec2, Vec3};
 use bevy_rapier2d::dynamics::Velocity;
 
 pub fn apply_velocity(
@@ -28,4 +28,26 @@ pub fn apply_jump_velocity(
     } else if !grounded.0 {
         velocity.linvel.y -= player.speed * 0.1;
     }
-}
\ No newline at end of file
+}
+
+/// Movement step shared by the server simulation and the client prediction,
+/// so both sides derive the same velocity from the same input.
+pub fn step_player_movement(
+    player: &Player,
+    input: &PlayerInput,
+    velocity: &mut Velocity,
+    jump_counter: &mut JumpCounter,
+    grounded: &Grounded,
+) {
+    apply_velocity(player, input, velocity);
+    apply_jump_velocity(player, input, velocity, jump_counter, grounded);
+}
+
+pub fn integrate_translation(
+    translation: &mut Vec3,
+    velocity: &Velocity,
+    delta_secs: f32,
+) {
+    translation.x += velocity.linvel.x * delta_secs;
+    translation.y += velocity.linvel.y * delta_secs;
+}
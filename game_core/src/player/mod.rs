@@ -2,4 +2,5 @@ pub mod component;
 pub mod texture;
 pub mod command;
 pub mod animation;
-pub mod math;
\ No newline at end of file
+pub mod math;
+pub mod system;
\ No newline at end of file
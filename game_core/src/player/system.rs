@@ -0,0 +1,14 @@
+use crate::player::component::Invulnerable;
+use bevy::prelude::{Commands, Entity, Query, Res, Time};
+
+pub fn expire_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable)>,
+) {
+    for (entity, mut invulnerable) in query.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use bevy::prelude::Component;
+
+/// Atlas frame and orientation of an entity's sprite, kept as plain data so the
+/// server can animate and replicate it without loading any image.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct SpriteFrame {
+    pub index: usize,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
@@ -58,4 +58,24 @@ macro_rules! make_weapon_fx_texture {
             }
         )
     };
-}
\ No newline at end of file
+}
+#[macro_export]
+macro_rules! make_monster_texture {
+    ($monster_type:expr, $start_min:expr, $frame_size:expr) => {
+        texture(
+            MONSTER_LAYOUT_SIZE,
+            $start_min,
+            MONSTERS,
+            $monster_type,
+            MONSTER_FRAME_COUNT,
+            $frame_size,
+            MONSTER_FRAME_PADDING,
+            |layout, typ, path| MonsterTextureEntity {
+                texture_atlas_layout: layout,
+                animation_indices: AnimationIndices { first: 0, last: MONSTER_FRAME_COUNT - 1 },
+                texture_path: path,
+                monster_type: typ,
+            }
+        )
+    };
+}
@@ -1,4 +1,5 @@
 pub mod system;
+pub mod component;
 pub mod frame;
 pub mod entity;
 
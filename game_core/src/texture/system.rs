@@ -1,13 +1,15 @@
+use crate::monster::texture::{MonsterTextureEntity, MonsterTextures};
 use crate::player::texture::{PlayerTextureEntity, PlayerTextures};
+use crate::texture::component::SpriteFrame;
 use crate::texture::entity::HasTextureEntityType;
 use crate::texture::frame::generate_frames;
 use crate::weapon::fx_texture::{WeaponFxTextureEntity, WeaponFxTextures};
 use crate::weapon::texture::{WeaponTextureEntity, WeaponTextures};
 use bevy::asset::{AssetServer, Assets, Handle};
-use bevy::image::{Image, TextureAtlasLayout};
+use bevy::image::{Image, TextureAtlas, TextureAtlasLayout};
 use bevy::log::error;
 use bevy::math::UVec2;
-use bevy::prelude::{Commands, Res, ResMut};
+use bevy::prelude::{Commands, Res, ResMut, Sprite};
 use std::collections::HashMap;
 
 #[allow(clippy::too_many_arguments)]
@@ -70,6 +72,23 @@ where
     (image, texture_atlas_layout)
 }
 
+pub fn sprite_from_frame(
+    image: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+    sprite_frame: SpriteFrame,
+) -> Sprite {
+    Sprite {
+        image,
+        texture_atlas: Some(TextureAtlas {
+            layout,
+            index: sprite_frame.index,
+        }),
+        flip_x: sprite_frame.flip_x,
+        flip_y: sprite_frame.flip_y,
+        ..Default::default()
+    }
+}
+
 pub fn load_player_textures(mut commands: Commands, asset_server: Res<AssetServer>) {
     load_textures(
         &mut commands,
@@ -79,6 +98,15 @@ pub fn load_player_textures(mut commands: Commands, asset_server: Res<AssetServe
     );
 }
 
+pub fn load_monster_textures(mut commands: Commands, asset_server: Res<AssetServer>) {
+    load_textures(
+        &mut commands,
+        &asset_server,
+        MonsterTextureEntity::all,
+        MonsterTextures,
+    );
+}
+
 pub fn load_weapon_fx_textures(mut commands: Commands, asset_server: Res<AssetServer>) {
     load_textures(
         &mut commands,
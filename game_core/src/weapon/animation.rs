@@ -1,4 +1,5 @@
 use crate::player::component::{Player, PlayerChildren, PlayerInput};
+use crate::texture::component::SpriteFrame;
 use crate::texture::math::is_face_right;
 use crate::weapon::component::{PivotDisk, Weapon};
 use bevy::math::Quat;
@@ -7,30 +8,18 @@ use bevy::prelude::{Query, Sprite, Transform, With};
 pub fn animate_weapons(
     player_query: Query<(&PlayerInput, &PlayerChildren), With<Player>>,
     mut pivot_query: Query<&mut Transform, With<PivotDisk>>,
-    mut weapon_query: Query<&mut Sprite, With<Weapon>>,
+    mut weapon_query: Query<&mut SpriteFrame, With<Weapon>>,
 ) {
     for (input, player_children) in player_query.iter() {
         if let Ok(mut pivot_transform) = pivot_query.get_mut(player_children.pivot) {
-            if let Ok(mut weapon_sprite) = weapon_query.get_mut(player_children.weapon) {
-                move_weapon(
-                    &mut pivot_transform,
-                    &mut weapon_sprite,
-                    input.aim_direction,
-                );
+            if let Ok(mut weapon_frame) = weapon_query.get_mut(player_children.weapon) {
+                weapon_rotation(&mut pivot_transform, input.aim_direction);
+                (weapon_frame.flip_x, weapon_frame.flip_y) = weapon_flip(input.aim_direction);
             }
         }
     }
 }
 
-pub fn move_weapon(
-    pivot_transform: &mut Transform,
-    weapon_sprite: &mut Sprite,
-    aim_direction: f32,
-) {
-    weapon_rotation(pivot_transform, aim_direction);
-    weapon_sprite_flip(weapon_sprite, aim_direction);
-}
-
 pub fn weapon_rotation(
     transform: &mut Transform,
     aim_direction: f32,
@@ -47,12 +36,15 @@ pub fn weapon_sprite_flip(
     weapon_sprite: &mut Sprite,
     aim_direction: f32,
 ) {
+    (weapon_sprite.flip_x, weapon_sprite.flip_y) = weapon_flip(aim_direction);
+}
+
+/// `(flip_x, flip_y)` of the weapon sprite for the given aim.
+fn weapon_flip(aim_direction: f32) -> (bool, bool) {
     let face_right = is_face_right(aim_direction);
     if face_right {
-        weapon_sprite.flip_y = !face_right;
-        weapon_sprite.flip_x = face_right;
+        (face_right, !face_right)
     } else {
-        weapon_sprite.flip_y = !face_right;
-        weapon_sprite.flip_x = !face_right;
+        (!face_right, !face_right)
     }
 }
@@ -1,8 +1,10 @@
 use crate::weapon::fx_texture::PISTOL_FX_SIZE;
-use bevy::math::Vec2;
-use bevy::prelude::{Component, Name};
+use bevy::math::{Vec2, Vec3};
+use crate::weapon::definition::GrenadeDefinition;
+use bevy::prelude::{Component, Entity, Name, Timer, TimerMode};
 use bevy_rapier2d::dynamics::{GravityScale, LockedAxes, RigidBody, Velocity};
-use bevy_rapier2d::geometry::{Collider, Friction};
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, Friction, Restitution, Sensor};
+use bevy_renet2::prelude::ClientId;
 
 #[derive(Component, Debug, Clone, Default)]
 pub struct Weapon;
@@ -10,11 +12,67 @@ pub struct Weapon;
 #[derive(Component, Debug, Clone, Default)]
 pub struct PivotDisk;
 
+#[derive(Component, Debug, Clone)]
+pub struct ProjectileOwner(pub ClientId);
 
-const WEAPON_FX_SPEED: f32 = 1000.0;
+/// Health removed from the player a projectile hits.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ProjectileDamage(pub u32);
+
+/// Where a projectile was fired from and how far it may travel.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ProjectileRange {
+    pub origin: Vec3,
+    pub max_distance: f32,
+}
+
+impl ProjectileRange {
+    pub fn travelled(&self, position: Vec3) -> f32 {
+        self.origin.truncate().distance(position.truncate())
+    }
+}
+
+/// Projectile exploding when its fuse runs out or when it touches a player other than its owner.
+#[derive(Component, Debug, Clone)]
+pub struct Grenade {
+    pub fuse: Timer,
+    pub explosion_radius: f32,
+    pub knockback_speed: f32,
+}
+
+impl Grenade {
+    pub fn new(definition: &GrenadeDefinition) -> Self {
+        Self {
+            fuse: Timer::from_seconds(definition.fuse_secs, TimerMode::Once),
+            explosion_radius: definition.explosion_radius,
+            knockback_speed: definition.knockback_speed,
+        }
+    }
+}
+
+/// Shot of several pellets, replicated as a single entity: clients rebuild
+/// each pellet position from the seed instead of receiving it every tick.
+#[derive(Component, Debug, Clone)]
+pub struct PelletVolley {
+    pub origin: Vec3,
+    pub aim_direction: f32,
+    pub spread_degrees: f32,
+    pub seed: u64,
+    pub pellet_count: u8,
+    pub speed: f32,
+    pub fired_tick: u64,
+}
+
+/// One pellet of a `PelletVolley`, simulated on the server.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Pellet {
+    pub volley: Entity,
+    pub index: u8,
+}
 
 pub fn spawn_weapon_fx_physics_bundle(
     aim_direction: f32,
+    projectile_speed: f32,
 ) -> (
     Name,
     RigidBody,
@@ -23,16 +81,45 @@ pub fn spawn_weapon_fx_physics_bundle(
     Collider,
     GravityScale,
     Friction,
+    Sensor,
+    ActiveEvents,
 ) {
     (
         Name::new("WeaponFX Physics"),
         RigidBody::Dynamic,
         LockedAxes::ROTATION_LOCKED,
-        Velocity::linear(Vec2::new(aim_direction.cos() * WEAPON_FX_SPEED, aim_direction.sin() * WEAPON_FX_SPEED)),
+        Velocity::linear(Vec2::from_angle(aim_direction) * projectile_speed),
         Collider::ball((PISTOL_FX_SIZE.y / 2) as f32),
         GravityScale(0.0),
         Friction::coefficient(0.0),
+        Sensor,
+        ActiveEvents::COLLISION_EVENTS,
     )
 }
 
-
+/// Solid, gravity-affected body bouncing off the ground, replacing the straight-line bundle for grenades.
+pub fn spawn_grenade_physics_bundle(
+    aim_direction: f32,
+    projectile_speed: f32,
+    definition: &GrenadeDefinition,
+) -> (
+    Name,
+    RigidBody,
+    LockedAxes,
+    Velocity,
+    Collider,
+    GravityScale,
+    Restitution,
+    ActiveEvents,
+) {
+    (
+        Name::new("Grenade Physics"),
+        RigidBody::Dynamic,
+        LockedAxes::ROTATION_LOCKED,
+        Velocity::linear(Vec2::from_angle(aim_direction) * projectile_speed),
+        Collider::ball((PISTOL_FX_SIZE.y / 2) as f32),
+        GravityScale(definition.gravity_scale),
+        Restitution::coefficient(definition.restitution),
+        ActiveEvents::COLLISION_EVENTS,
+    )
+}
@@ -0,0 +1,160 @@
+use crate::weapon::texture::WeaponTextureType;
+use bevy::prelude::{Component, Resource};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// File under the asset directory describing every weapon.
+pub const WEAPON_DEFINITIONS_PATH: &str = "weapons.ron";
+
+/// Most projectiles a shot can fire, so a volley fits in `NetworkedPelletVolley::alive_pellets`.
+pub const MAX_PROJECTILES_PER_SHOT: u32 = 32;
+
+/// Damage lost over the distance a projectile travelled.
+#[derive(Component, Debug, Clone, Copy, Deserialize)]
+pub struct DamageFalloff {
+    /// Distance up to which the full damage is dealt.
+    pub start: f32,
+    /// Damage dealt at the end of the weapon range.
+    pub min_damage: u32,
+}
+
+impl DamageFalloff {
+    /// Interpolates linearly from `damage` at `start` down to `min_damage` at `range`.
+    pub fn damage_at(&self, damage: u32, distance: f32, range: f32) -> u32 {
+        if distance <= self.start || range <= self.start {
+            return damage;
+        }
+
+        let progress = ((distance - self.start) / (range - self.start)).clamp(0.0, 1.0);
+        let min_damage = self.min_damage.min(damage) as f32;
+        (damage as f32 + (min_damage - damage as f32) * progress).round() as u32
+    }
+}
+
+/// Ballistic projectile that bounces off the level and explodes instead of hitting directly.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct GrenadeDefinition {
+    pub gravity_scale: f32,
+    /// Bounciness against the ground, from 0 (none) to 1 (no energy lost).
+    pub restitution: f32,
+    /// Time before the grenade explodes when it touches no player.
+    pub fuse_secs: f32,
+    pub explosion_radius: f32,
+    /// Speed given to players at the centre of the explosion, decreasing towards its edge.
+    pub knockback_speed: f32,
+}
+
+impl GrenadeDefinition {
+    fn validate(&self) -> Result<(), String> {
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+        if !positive(self.fuse_secs) || !positive(self.explosion_radius) {
+            return Err("fuse_secs et explosion_radius doivent être positifs".to_string());
+        }
+        if !(self.gravity_scale.is_finite() && self.knockback_speed.is_finite() && (0.0..=1.0).contains(&self.restitution)) {
+            return Err(format!("grenade invalide: {self:?}"));
+        }
+        Ok(())
+    }
+}
+
+/// Gameplay stats of one weapon, as written in `weapons.ron`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeaponDefinition {
+    /// Shots per second.
+    pub fire_rate: f32,
+    /// Keeps firing while the trigger is held instead of once per click.
+    pub automatic: bool,
+    pub magazine_size: u32,
+    /// Rounds carried besides the magazine at spawn, and the most an ammo pickup restocks to.
+    pub reserve_ammo: u32,
+    pub reload_secs: f32,
+    pub projectile_speed: f32,
+    /// Health removed by each projectile.
+    pub damage: u32,
+    /// Total angle, in degrees, projectiles are scattered over around the aim direction.
+    pub spread_degrees: f32,
+    /// Projectiles fired per shot.
+    pub projectile_count: u32,
+    /// Distance after which projectiles disappear; grenades last until their fuse ends instead.
+    pub range: f32,
+    /// Full damage over the whole range when absent.
+    #[serde(default)]
+    pub falloff: Option<DamageFalloff>,
+    /// Fires grenades dealing `damage` around their explosion instead of direct hits.
+    #[serde(default)]
+    pub grenade: Option<GrenadeDefinition>,
+}
+
+impl WeaponDefinition {
+    pub fn cooldown_secs(&self) -> f32 {
+        1.0 / self.fire_rate
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+        if !positive(self.fire_rate) {
+            return Err(format!("fire_rate invalide: {}", self.fire_rate));
+        }
+        if !positive(self.projectile_speed) {
+            return Err(format!("projectile_speed invalide: {}", self.projectile_speed));
+        }
+        if !positive(self.range) {
+            return Err(format!("range invalide: {}", self.range));
+        }
+        if let Some(falloff) = self.falloff
+            && !(falloff.start.is_finite() && (0.0..=self.range).contains(&falloff.start))
+        {
+            return Err(format!("falloff.start invalide: {}", falloff.start));
+        }
+        if !(self.reload_secs.is_finite() && self.reload_secs >= 0.0) {
+            return Err(format!("reload_secs invalide: {}", self.reload_secs));
+        }
+        if !(self.spread_degrees.is_finite() && self.spread_degrees >= 0.0) {
+            return Err(format!("spread_degrees invalide: {}", self.spread_degrees));
+        }
+        if self.magazine_size == 0 || self.projectile_count == 0 {
+            return Err("magazine_size et projectile_count doivent être positifs".to_string());
+        }
+        if self.projectile_count > MAX_PROJECTILES_PER_SHOT {
+            return Err(format!("projectile_count limité à {MAX_PROJECTILES_PER_SHOT}"));
+        }
+        if let Some(grenade) = &self.grenade {
+            grenade.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Resource, Deserialize)]
+#[serde(transparent)]
+pub struct WeaponDefinitions(pub HashMap<WeaponTextureType, WeaponDefinition>);
+
+impl WeaponDefinitions {
+    /// Reads `weapons.ron` from the asset directory; every weapon type must be defined.
+    pub fn load(asset_path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = asset_path.as_ref().join(WEAPON_DEFINITIONS_PATH);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("impossible de lire {}: {e}", path.display()))?;
+        let definitions: Self = ron::from_str(&content)
+            .map_err(|e| format!("définitions d'armes {} invalides: {e}", path.display()))?;
+
+        for weapon_texture_type in [
+            WeaponTextureType::Pistol,
+            WeaponTextureType::Shotgun,
+            WeaponTextureType::Rifle,
+            WeaponTextureType::GrenadeLauncher,
+        ] {
+            definitions.get(&weapon_texture_type)
+                .ok_or_else(|| format!("arme {weapon_texture_type:?} absente de {}", path.display()))?
+                .validate()
+                .map_err(|e| format!("arme {weapon_texture_type:?}: {e}"))?;
+        }
+
+        Ok(definitions)
+    }
+
+    pub fn get(&self, weapon_texture_type: &WeaponTextureType) -> Option<&WeaponDefinition> {
+        self.0.get(weapon_texture_type)
+    }
+}
@@ -43,6 +43,17 @@ impl From<&WeaponTextureType> for WeaponFxTextureType {
         }
     }
 }
+impl From<&WeaponFxTextureType> for WeaponTextureType {
+    fn from(value: &WeaponFxTextureType) -> Self {
+        match value {
+            WeaponFxTextureType::Pistol => WeaponTextureType::Pistol,
+            WeaponFxTextureType::Shotgun => WeaponTextureType::Shotgun,
+            WeaponFxTextureType::Rifle => WeaponTextureType::Rifle,
+            WeaponFxTextureType::GrenadeLauncher => WeaponTextureType::GrenadeLauncher,
+        }
+    }
+}
+
 pub struct WeaponFxTextureEntity {
     pub texture_atlas_layout: TextureAtlasLayout,
     pub animation_indices: AnimationIndices,
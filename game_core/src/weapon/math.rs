@@ -0,0 +1,69 @@
+use bevy::math::{Vec2, Vec3};
+use bevy_renet2::prelude::ClientId;
+
+/// Seed of a shot's spread, derived from values clients receive with the shot.
+pub fn spread_seed(tick: u64, owner: ClientId) -> u64 {
+    tick.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ owner
+}
+
+/// Directions, in radians, of `count` projectiles scattered over `spread_degrees` around `aim_direction`.
+/// The same seed always gives the same directions, on the server and on clients.
+pub fn projectile_directions(aim_direction: f32, spread_degrees: f32, count: u32, seed: u64) -> Vec<f32> {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let half_spread = spread_degrees.to_radians() / 2.0;
+    (0..count)
+        .map(|_| aim_direction + (rng.f32() * 2.0 - 1.0) * half_spread)
+        .collect()
+}
+
+/// Position of a projectile moving in a straight line `elapsed_secs` after leaving `origin`.
+pub fn projectile_position(origin: Vec3, direction: f32, speed: f32, elapsed_secs: f32) -> Vec3 {
+    origin + (Vec2::from_angle(direction) * speed * elapsed_secs).extend(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weapon::definition::{DamageFalloff, MAX_PROJECTILES_PER_SHOT};
+
+    #[test]
+    fn same_seed_gives_same_directions() {
+        let seed = spread_seed(1234, 7);
+        assert_eq!(projectile_directions(0.5, 20.0, 8, seed), projectile_directions(0.5, 20.0, 8, seed));
+        assert_ne!(seed, spread_seed(1235, 7));
+        assert_ne!(seed, spread_seed(1234, 8));
+    }
+
+    #[test]
+    fn directions_stay_within_the_spread() {
+        let half_spread = 30.0_f32.to_radians() / 2.0;
+        for tick in 0..100 {
+            let directions = projectile_directions(1.0, 30.0, MAX_PROJECTILES_PER_SHOT, spread_seed(tick, 3));
+            assert_eq!(directions.len(), MAX_PROJECTILES_PER_SHOT as usize);
+            assert!(directions.iter().all(|direction| (direction - 1.0).abs() <= half_spread));
+        }
+    }
+
+    #[test]
+    fn single_projectile_without_spread_follows_the_aim() {
+        assert_eq!(projectile_directions(-2.0, 0.0, 1, spread_seed(42, 1)), vec![-2.0]);
+        assert_eq!(projectile_directions(-2.0, 10.0, 1, spread_seed(42, 1)).len(), 1);
+    }
+
+    #[test]
+    fn falloff_keeps_full_damage_until_start_and_reaches_minimum_at_range() {
+        let falloff = DamageFalloff { start: 100.0, min_damage: 4 };
+        assert_eq!(falloff.damage_at(20, 0.0, 500.0), 20);
+        assert_eq!(falloff.damage_at(20, 100.0, 500.0), 20);
+        assert_eq!(falloff.damage_at(20, 300.0, 500.0), 12);
+        assert_eq!(falloff.damage_at(20, 500.0, 500.0), 4);
+        assert_eq!(falloff.damage_at(20, 900.0, 500.0), 4);
+    }
+
+    #[test]
+    fn falloff_never_raises_damage() {
+        let falloff = DamageFalloff { start: 0.0, min_damage: 50 };
+        assert_eq!(falloff.damage_at(20, 500.0, 500.0), 20);
+        assert_eq!(falloff.damage_at(20, 250.0, 100.0), 20);
+    }
+}
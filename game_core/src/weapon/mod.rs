@@ -5,4 +5,6 @@ pub mod system;
 pub mod state;
 pub mod fx_texture;
 pub mod command;
+pub mod definition;
+pub mod math;
 pub mod animation;
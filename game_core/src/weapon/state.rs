@@ -0,0 +1,176 @@
+use crate::weapon::definition::WeaponDefinition;
+use crate::weapon::texture::WeaponTextureType;
+use bevy::prelude::{Component, Timer, TimerMode};
+
+pub const WEAPON_SWAP_SECS: f32 = 0.3;
+
+/// Ammunition and timers of one weapon a player owns.
+#[derive(Debug, Clone)]
+pub struct WeaponState {
+    pub ammo: u32,
+    /// Rounds outside the magazine, spent by reloads.
+    pub reserve: u32,
+    pub cooldown_secs: f32,
+    /// Time left before the magazine is full again, while reloading.
+    pub reload_secs: Option<f32>,
+    trigger_held: bool,
+}
+
+impl WeaponState {
+    pub fn new(definition: &WeaponDefinition) -> Self {
+        Self {
+            ammo: definition.magazine_size,
+            reserve: definition.reserve_ammo,
+            cooldown_secs: 0.0,
+            reload_secs: None,
+            trigger_held: false,
+        }
+    }
+
+    pub fn tick(&mut self, delta_secs: f32, definition: &WeaponDefinition) {
+        self.cooldown_secs = (self.cooldown_secs - delta_secs).max(0.0);
+
+        if let Some(reload_secs) = self.reload_secs.as_mut() {
+            *reload_secs -= delta_secs;
+            if *reload_secs <= 0.0 {
+                let loaded = (definition.magazine_size - self.ammo).min(self.reserve);
+                self.ammo += loaded;
+                self.reserve -= loaded;
+                self.reload_secs = None;
+            }
+        }
+    }
+
+    /// Only with rounds left in the reserve.
+    pub fn start_reload(&mut self, definition: &WeaponDefinition) {
+        if self.reload_secs.is_none() && self.ammo < definition.magazine_size && self.reserve > 0 {
+            self.reload_secs = Some(definition.reload_secs);
+        }
+    }
+
+    /// Whether a shot leaves the weapon this frame; semi-automatic weapons need the trigger released in between.
+    pub fn try_fire(&mut self, trigger: bool, definition: &WeaponDefinition) -> bool {
+        let pulled = trigger && (definition.automatic || !self.trigger_held);
+        self.trigger_held = trigger;
+
+        if !pulled || self.cooldown_secs > 0.0 || self.reload_secs.is_some() {
+            return false;
+        }
+        if self.ammo == 0 {
+            self.start_reload(definition);
+            return false;
+        }
+
+        self.ammo -= 1;
+        self.cooldown_secs = definition.cooldown_secs();
+        if self.ammo == 0 {
+            self.start_reload(definition);
+        }
+        true
+    }
+
+    /// Fills the reserve back up; returns whether any round was added.
+    pub fn restock(&mut self, definition: &WeaponDefinition) -> bool {
+        if self.reserve >= definition.reserve_ammo {
+            return false;
+        }
+        self.reserve = definition.reserve_ammo;
+        true
+    }
+}
+
+/// Weapon being drawn; the player cannot fire until the timer ends.
+#[derive(Component, Debug)]
+pub struct WeaponSwap {
+    pub weapon: WeaponTextureType,
+    pub timer: Timer,
+}
+
+impl WeaponSwap {
+    pub fn new(weapon: WeaponTextureType) -> Self {
+        Self {
+            weapon,
+            timer: Timer::from_seconds(WEAPON_SWAP_SECS, TimerMode::Once),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::component::WeaponInventory;
+    use crate::weapon::definition::WeaponDefinitions;
+    use std::collections::HashMap;
+
+    fn definition(magazine_size: u32, reserve_ammo: u32) -> WeaponDefinition {
+        WeaponDefinition {
+            fire_rate: 10.0,
+            automatic: true,
+            magazine_size,
+            reserve_ammo,
+            reload_secs: 1.0,
+            projectile_speed: 1000.0,
+            damage: 10,
+            spread_degrees: 0.0,
+            projectile_count: 1,
+            range: 1000.0,
+            falloff: None,
+            grenade: None,
+        }
+    }
+
+    fn empty_magazine(state: &mut WeaponState, definition: &WeaponDefinition) {
+        while state.ammo > 0 {
+            assert!(state.try_fire(true, definition));
+            state.tick(definition.cooldown_secs(), definition);
+        }
+    }
+
+    #[test]
+    fn reloads_move_rounds_from_the_reserve() {
+        let definition = definition(4, 6);
+        let mut state = WeaponState::new(&definition);
+
+        empty_magazine(&mut state, &definition);
+        state.tick(definition.reload_secs, &definition);
+        assert_eq!((state.ammo, state.reserve), (4, 2));
+
+        empty_magazine(&mut state, &definition);
+        state.tick(definition.reload_secs, &definition);
+        assert_eq!((state.ammo, state.reserve), (2, 0));
+    }
+
+    #[test]
+    fn empty_reserve_prevents_reloading() {
+        let definition = definition(4, 0);
+        let mut state = WeaponState::new(&definition);
+
+        empty_magazine(&mut state, &definition);
+        state.start_reload(&definition);
+
+        assert_eq!(state.reload_secs, None);
+        assert!(!state.try_fire(true, &definition));
+    }
+
+    #[test]
+    fn ammo_pickups_restock_every_owned_weapon_once() {
+        let pistol = definition(4, 8);
+        let rifle = definition(10, 20);
+        let definitions = WeaponDefinitions(HashMap::from([
+            (WeaponTextureType::Pistol, pistol.clone()),
+            (WeaponTextureType::Rifle, rifle.clone()),
+        ]));
+        let mut inventory = WeaponInventory::new(&[WeaponTextureType::Pistol, WeaponTextureType::Rifle], &definitions);
+        assert!(!inventory.restock(None, &definitions));
+
+        for (weapon, definition) in [(WeaponTextureType::Pistol, &pistol), (WeaponTextureType::Rifle, &rifle)] {
+            let state = inventory.state_mut(&weapon).unwrap();
+            empty_magazine(state, definition);
+            state.tick(definition.reload_secs, definition);
+        }
+        assert!(inventory.restock(None, &definitions));
+
+        assert_eq!(inventory.state(&WeaponTextureType::Pistol).unwrap().reserve, 8);
+        assert_eq!(inventory.state(&WeaponTextureType::Rifle).unwrap().reserve, 20);
+    }
+}
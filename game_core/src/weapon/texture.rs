@@ -36,21 +36,21 @@ pub struct WeaponTextureEntity {
 }
 
 
-const WEAPON_LAYOUT_SIZE: UVec2 = UVec2::new(502, 448);
+pub const WEAPON_LAYOUT_SIZE: UVec2 = UVec2::new(502, 448);
 const WEAPON_FRAME_PADDING: u32 = 0;
 const WEAPON_FRAME_COUNT: u32 = 1;
 
 pub const PISTOL_SIZE: UVec2 = UVec2::new(51, 23);
 const PISTOL_START_MIN: UVec2 = UVec2::new(358, 200);
 
-const SHOTGUN_SIZE: UVec2 = UVec2::new(51, 23);
-const SHOTGUN_START_MIN: UVec2 = UVec2::new(358, 200);
+const SHOTGUN_SIZE: UVec2 = UVec2::new(59, 19);
+const SHOTGUN_START_MIN: UVec2 = UVec2::new(324, 55);
 
-const RIFLE_SIZE: UVec2 = UVec2::new(51, 23);
-const RIFLE_START_MIN: UVec2 = UVec2::new(358, 200);
+const RIFLE_SIZE: UVec2 = UVec2::new(60, 26);
+const RIFLE_START_MIN: UVec2 = UVec2::new(107, 48);
 
-const GRENADE_LAUNCHER_SIZE: UVec2 = UVec2::new(51, 23);
-const GRENADE_LAUNCHER_START_MIN: UVec2 = UVec2::new(358, 200);
+const GRENADE_LAUNCHER_SIZE: UVec2 = UVec2::new(57, 34);
+const GRENADE_LAUNCHER_START_MIN: UVec2 = UVec2::new(123, 192);
 
 
 impl WeaponTextureEntity {
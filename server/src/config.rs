@@ -0,0 +1,319 @@
+use bevy::prelude::Resource;
+use game_core::config::{load_ron_config, CliArgs};
+use game_core::game_mode::component::Team;
+use game_core::decor::level::{is_valid_level_name, DEFAULT_LEVEL};
+use game_core::network::auth::PRIVATE_KEY_PATH;
+use game_core::network::network::{PROTOCOL_ID, SERVER_TICK_RATE};
+use game_core::weapon::texture::WeaponTextureType;
+use serde::Deserialize;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+
+pub const SERVER_CONFIG_PATH: &str = "server.ron";
+
+/// How a spawn point is picked when a player joins or respawns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum SpawnStrategy {
+    /// Spawn point whose nearest living enemy is the farthest away.
+    #[default]
+    FarthestFromEnemies,
+    /// Each spawn point in turn.
+    RoundRobin,
+}
+
+impl FromStr for SpawnStrategy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "farthest" | "farthest-from-enemies" => Ok(Self::FarthestFromEnemies),
+            "round-robin" => Ok(Self::RoundRobin),
+            _ => Err("attendu farthest ou round-robin".to_string()),
+        }
+    }
+}
+
+/// Rules the server runs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum GameModeKind {
+    /// Every player for themselves; level monsters respawn on their own.
+    #[default]
+    Deathmatch,
+    /// Two teams scoring together, otherwise like `Deathmatch`.
+    TeamDeathmatch,
+    /// Two teams scoring by bringing the enemy flag to their own base.
+    CaptureTheFlag,
+    /// Players team up against escalating monster waves from `waves.ron`.
+    Waves,
+}
+
+impl GameModeKind {
+    /// Players get a `Team` when they join.
+    pub fn has_teams(&self) -> bool {
+        matches!(self, GameModeKind::TeamDeathmatch | GameModeKind::CaptureTheFlag)
+    }
+}
+
+impl FromStr for GameModeKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "deathmatch" | "ffa" => Ok(Self::Deathmatch),
+            "team-deathmatch" | "tdm" => Ok(Self::TeamDeathmatch),
+            "capture-the-flag" | "ctf" => Ok(Self::CaptureTheFlag),
+            "waves" | "survival" => Ok(Self::Waves),
+            _ => Err("attendu deathmatch, team-deathmatch, capture-the-flag ou waves".to_string()),
+        }
+    }
+}
+
+/// Match lifecycle of the scoring modes.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MatchSettings {
+    /// Players needed before the warmup countdown starts.
+    pub min_players: usize,
+    pub warmup_secs: f32,
+    /// Score ending the match early; 0 disables it.
+    pub score_limit: u32,
+    pub time_limit_secs: f32,
+    /// Time results are shown before the next warmup.
+    pub results_secs: f32,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        Self {
+            min_players: 2,
+            warmup_secs: 15.0,
+            score_limit: 20,
+            time_limit_secs: 600.0,
+            results_secs: 10.0,
+        }
+    }
+}
+
+/// Server settings, read from `server.ron` (or `--config <path>`) and overridden by command-line options.
+#[derive(Debug, Clone, Resource, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    /// Local address the UDP socket binds to, e.g. `0.0.0.0` to listen on every interface.
+    pub bind_address: IpAddr,
+    /// Address clients reach the server at; defaults to `bind_address`, so it is required when that one is unspecified.
+    pub public_address: Option<IpAddr>,
+    pub port: u16,
+    pub max_clients: usize,
+    pub tick_rate: f64,
+    pub protocol_id: u64,
+    pub asset_path: String,
+    pub private_key_path: String,
+    /// Accept clients without a connect token, for local development.
+    pub unsecure: bool,
+    /// Run without a window or renderer, e.g. on a dedicated or CI machine.
+    pub headless: bool,
+    pub spawn_strategy: SpawnStrategy,
+    pub game_mode: GameModeKind,
+    pub match_settings: MatchSettings,
+    /// Teammates hurt each other in the team modes.
+    pub friendly_fire: bool,
+    /// Inventory players join with; the first weapon is drawn.
+    pub starting_weapons: Vec<WeaponTextureType>,
+    /// Level file name under `levels/`, without the `.level.ron` extension; Tiled maps keep theirs.
+    pub level: String,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            public_address: None,
+            port: 5000,
+            max_clients: 64,
+            tick_rate: SERVER_TICK_RATE,
+            protocol_id: PROTOCOL_ID,
+            asset_path: "../assets".to_string(),
+            private_key_path: PRIVATE_KEY_PATH.to_string(),
+            unsecure: false,
+            headless: false,
+            spawn_strategy: SpawnStrategy::default(),
+            game_mode: GameModeKind::default(),
+            match_settings: MatchSettings::default(),
+            friendly_fire: false,
+            starting_weapons: vec![WeaponTextureType::Pistol],
+            level: DEFAULT_LEVEL.to_string(),
+        }
+    }
+}
+
+impl ServerSettings {
+    pub fn load(args: &CliArgs) -> Result<Self, String> {
+        let mut settings: Self = load_ron_config(args.value("--config"), SERVER_CONFIG_PATH)?;
+
+        if let Some(bind_address) = args.parse("--bind")? {
+            settings.bind_address = bind_address;
+        }
+        if let Some(public_address) = args.parse("--public")? {
+            settings.public_address = Some(public_address);
+        }
+        if let Some(port) = args.parse("--port")? {
+            settings.port = port;
+        }
+        if let Some(max_clients) = args.parse("--max-clients")? {
+            settings.max_clients = max_clients;
+        }
+        if let Some(tick_rate) = args.parse("--tick-rate")? {
+            settings.tick_rate = tick_rate;
+        }
+        if let Some(protocol_id) = args.parse("--protocol-id")? {
+            settings.protocol_id = protocol_id;
+        }
+        if let Some(asset_path) = args.value("--assets") {
+            settings.asset_path = asset_path.to_string();
+        }
+        if let Some(private_key_path) = args.value("--private-key") {
+            settings.private_key_path = private_key_path.to_string();
+        }
+        if let Some(level) = args.value("--level") {
+            settings.level = level.to_string();
+        }
+        if let Some(spawn_strategy) = args.parse("--spawn-strategy")? {
+            settings.spawn_strategy = spawn_strategy;
+        }
+        if let Some(game_mode) = args.parse("--mode")? {
+            settings.game_mode = game_mode;
+        }
+        if let Some(score_limit) = args.parse("--score-limit")? {
+            settings.match_settings.score_limit = score_limit;
+        }
+        if let Some(time_limit_secs) = args.parse("--time-limit")? {
+            settings.match_settings.time_limit_secs = time_limit_secs;
+        }
+        if let Some(min_players) = args.parse("--min-players")? {
+            settings.match_settings.min_players = min_players;
+        }
+        settings.friendly_fire |= args.flag("--friendly-fire");
+        settings.unsecure |= args.flag("--unsecure");
+        settings.headless |= args.flag("--headless");
+
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(self.tick_rate.is_finite() && self.tick_rate > 0.0) {
+            return Err(format!("tick_rate invalide: {}", self.tick_rate));
+        }
+
+        let public_ip = self.public_addr().ip();
+        if public_ip.is_unspecified() {
+            return Err(format!("adresse publique {public_ip} invalide: public_address est requis quand bind_address n'est pas une adresse précise"));
+        }
+
+        let match_settings = &self.match_settings;
+        if ![match_settings.warmup_secs, match_settings.time_limit_secs, match_settings.results_secs]
+            .iter()
+            .all(|secs| secs.is_finite() && *secs > 0.0)
+        {
+            return Err(format!("durées de match invalides: {match_settings:?}"));
+        }
+
+        if self.starting_weapons.is_empty() {
+            return Err("starting_weapons ne peut pas être vide".to_string());
+        }
+
+        if !is_valid_level_name(&self.level) {
+            return Err(format!("nom de niveau invalide: {}", self.level));
+        }
+
+        Ok(())
+    }
+
+    /// Whether a player of `attacker_team` hurts one of `victim_team`; survival is cooperative.
+    pub fn player_damage(&self, attacker_team: Option<Team>, victim_team: Option<Team>) -> bool {
+        match (attacker_team, victim_team) {
+            _ if self.game_mode == GameModeKind::Waves => false,
+            (Some(attacker_team), Some(victim_team)) if attacker_team == victim_team => self.friendly_fire,
+            _ => true,
+        }
+    }
+
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.bind_address, self.port)
+    }
+
+    pub fn public_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.public_address.unwrap_or(self.bind_address), self.port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/server.example.ron");
+
+    fn load(args: &[&str]) -> Result<ServerSettings, String> {
+        let args = ["--config", EXAMPLE_CONFIG].iter().chain(args).map(|arg| arg.to_string()).collect();
+        ServerSettings::load(&CliArgs::new(args))
+    }
+
+    #[test]
+    fn example_config_is_valid() {
+        let settings = load(&[]).unwrap();
+        assert_eq!(settings.bind_addr(), "0.0.0.0:5000".parse().unwrap());
+        assert_eq!(settings.public_addr(), "192.168.1.10:5000".parse().unwrap());
+        assert_eq!(settings.starting_weapons.len(), 4);
+    }
+
+    #[test]
+    fn command_line_overrides_the_config() {
+        let settings = load(&["--port", "6000", "--mode", "waves", "--level", "arena.tmx", "--friendly-fire"]).unwrap();
+        assert_eq!(settings.public_addr(), "192.168.1.10:6000".parse().unwrap());
+        assert_eq!(settings.game_mode, GameModeKind::Waves);
+        assert_eq!(settings.level, "arena.tmx");
+        assert!(settings.friendly_fire);
+    }
+
+    #[test]
+    fn invalid_options_are_refused() {
+        assert!(load(&["--port", "port"]).is_err());
+        assert!(load(&["--tick-rate", "0"]).is_err());
+        assert!(load(&["--level", "../default"]).is_err());
+        assert!(load(&["--public", "0.0.0.0"]).is_err());
+        assert!(ServerSettings::load(&CliArgs::new(vec!["--config".to_string(), "missing.ron".to_string()])).is_err());
+    }
+
+    #[test]
+    fn unspecified_bind_address_needs_a_public_address() {
+        let settings = ServerSettings { bind_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED), ..Default::default() };
+        assert!(settings.validate().is_err());
+
+        let settings = ServerSettings { public_address: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10))), ..settings };
+        assert!(settings.validate().is_ok());
+        assert!(ServerSettings::default().validate().is_ok());
+    }
+
+    fn settings(game_mode: GameModeKind, friendly_fire: bool) -> ServerSettings {
+        ServerSettings { game_mode, friendly_fire, ..Default::default() }
+    }
+
+    #[test]
+    fn friendly_fire_only_decides_between_teammates() {
+        for friendly_fire in [false, true] {
+            let settings = settings(GameModeKind::TeamDeathmatch, friendly_fire);
+            assert_eq!(settings.player_damage(Some(Team::Red), Some(Team::Red)), friendly_fire);
+            assert!(settings.player_damage(Some(Team::Red), Some(Team::Blue)));
+            assert!(settings.player_damage(None, None));
+        }
+    }
+
+    #[test]
+    fn players_never_hurt_each_other_in_waves() {
+        for friendly_fire in [false, true] {
+            let settings = settings(GameModeKind::Waves, friendly_fire);
+            assert!(!settings.player_damage(None, None));
+            assert!(!settings.player_damage(Some(Team::Red), Some(Team::Blue)));
+        }
+    }
+}
@@ -0,0 +1,47 @@
+pub mod capture_the_flag;
+pub mod deathmatch;
+pub mod team_deathmatch;
+
+use crate::config::GameModeKind;
+use crate::system::respawn_system::PlayerKilled;
+use bevy::prelude::Resource;
+use bevy_renet2::prelude::ClientId;
+use capture_the_flag::CaptureTheFlag;
+use deathmatch::Deathmatch;
+use game_core::game_mode::component::{ScoreEntry, Team};
+use team_deathmatch::TeamDeathmatch;
+
+/// Scoring rules of a match; the lifecycle around it is shared by every mode.
+pub trait GameMode: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn player_joined(&mut self, _client_id: ClientId) {}
+
+    fn player_left(&mut self, _client_id: ClientId) {}
+
+    /// Only called while the match is in progress; `killer` is `None` for falls and monster kills.
+    fn player_killed(&mut self, kill: &PlayerKilled);
+
+    /// Only called while the match is in progress, for the team that brought the enemy flag home.
+    fn flag_captured(&mut self, _team: Team) {}
+
+    fn scores(&self) -> Vec<ScoreEntry>;
+
+    /// Clears the scores, keeping the players.
+    fn reset_scores(&mut self);
+}
+
+#[derive(Resource)]
+pub struct ActiveGameMode(pub Box<dyn GameMode>);
+
+impl GameModeKind {
+    /// Scoring rules for this mode, `None` for the survival mode which has its own lifecycle.
+    pub fn create(&self) -> Option<ActiveGameMode> {
+        match self {
+            GameModeKind::Deathmatch => Some(ActiveGameMode(Box::new(Deathmatch::default()))),
+            GameModeKind::TeamDeathmatch => Some(ActiveGameMode(Box::new(TeamDeathmatch::default()))),
+            GameModeKind::CaptureTheFlag => Some(ActiveGameMode(Box::new(CaptureTheFlag::default()))),
+            GameModeKind::Waves => None,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::game_mode::GameMode;
+use crate::system::respawn_system::PlayerKilled;
+use game_core::game_mode::component::{ScoreEntry, ScoreOwner, Team};
+use std::collections::HashMap;
+
+/// Red against Blue; only captures score, kills just send the victim back to a spawn point.
+#[derive(Debug, Default)]
+pub struct CaptureTheFlag {
+    captures: HashMap<Team, i32>,
+}
+
+impl GameMode for CaptureTheFlag {
+    fn name(&self) -> &'static str {
+        "Capture the Flag"
+    }
+
+    fn player_killed(&mut self, _kill: &PlayerKilled) {}
+
+    fn flag_captured(&mut self, team: Team) {
+        *self.captures.entry(team).or_default() += 1;
+    }
+
+    fn scores(&self) -> Vec<ScoreEntry> {
+        [Team::Red, Team::Blue].into_iter()
+            .map(|team| ScoreEntry { owner: ScoreOwner::Team(team), score: self.captures.get(&team).copied().unwrap_or(0) })
+            .collect()
+    }
+
+    fn reset_scores(&mut self) {
+        self.captures.clear();
+    }
+}
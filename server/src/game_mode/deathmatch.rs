@@ -0,0 +1,89 @@
+use crate::game_mode::GameMode;
+use crate::system::respawn_system::PlayerKilled;
+use bevy_renet2::prelude::ClientId;
+use game_core::game_mode::component::{ScoreEntry, ScoreOwner};
+use std::collections::HashMap;
+
+/// Free-for-all: a point per kill, a point lost per suicide or fall.
+#[derive(Debug, Default)]
+pub struct Deathmatch {
+    scores: HashMap<ClientId, i32>,
+}
+
+impl GameMode for Deathmatch {
+    fn name(&self) -> &'static str {
+        "Deathmatch"
+    }
+
+    fn player_joined(&mut self, client_id: ClientId) {
+        self.scores.insert(client_id, 0);
+    }
+
+    fn player_left(&mut self, client_id: ClientId) {
+        self.scores.remove(&client_id);
+    }
+
+    fn player_killed(&mut self, kill: &PlayerKilled) {
+        match kill.killer {
+            // A killer who already left, e.g. through a late grenade, gets no row back.
+            Some(killer) if killer != kill.victim => {
+                if let Some(score) = self.scores.get_mut(&killer) {
+                    *score += 1;
+                }
+            }
+            _ => *self.scores.entry(kill.victim).or_default() -= 1,
+        }
+    }
+
+    fn scores(&self) -> Vec<ScoreEntry> {
+        let mut scores: Vec<ScoreEntry> = self.scores.iter()
+            .map(|(client_id, score)| ScoreEntry { owner: ScoreOwner::Player(*client_id), score: *score })
+            .collect();
+        scores.sort_by_key(|entry| -entry.score);
+        scores
+    }
+
+    fn reset_scores(&mut self) {
+        self.scores.values_mut().for_each(|score| *score = 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kill(victim: ClientId, killer: Option<ClientId>) -> PlayerKilled {
+        PlayerKilled { victim, killer, victim_team: None, killer_team: None }
+    }
+
+    fn score(mode: &Deathmatch, client_id: ClientId) -> i32 {
+        mode.scores[&client_id]
+    }
+
+    #[test]
+    fn kills_score_and_suicides_cost_a_point() {
+        let mut mode = Deathmatch::default();
+        mode.player_joined(1);
+        mode.player_joined(2);
+
+        mode.player_killed(&kill(2, Some(1)));
+        mode.player_killed(&kill(2, Some(1)));
+        mode.player_killed(&kill(1, Some(1)));
+        mode.player_killed(&kill(2, None));
+
+        assert_eq!((score(&mode, 1), score(&mode, 2)), (1, -1));
+        assert_eq!(mode.scores()[0], ScoreEntry { owner: ScoreOwner::Player(1), score: 1 });
+    }
+
+    #[test]
+    fn killers_who_left_are_not_scored() {
+        let mut mode = Deathmatch::default();
+        mode.player_joined(1);
+        mode.player_joined(2);
+        mode.player_left(2);
+
+        mode.player_killed(&kill(1, Some(2)));
+
+        assert_eq!(mode.scores(), vec![ScoreEntry { owner: ScoreOwner::Player(1), score: 0 }]);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::game_mode::GameMode;
+use crate::system::respawn_system::PlayerKilled;
+use game_core::game_mode::component::{ScoreEntry, ScoreOwner, Team};
+use std::collections::HashMap;
+
+/// Red against Blue; teams live on the player entities and team kills cost a point.
+#[derive(Debug, Default)]
+pub struct TeamDeathmatch {
+    scores: HashMap<Team, i32>,
+}
+
+impl GameMode for TeamDeathmatch {
+    fn name(&self) -> &'static str {
+        "Team Deathmatch"
+    }
+
+    fn player_killed(&mut self, kill: &PlayerKilled) {
+        let Some(victim_team) = kill.victim_team else { return };
+        match (kill.killer, kill.killer_team) {
+            // The killer already left, so its team is unknown: nobody scores.
+            (Some(_), None) => {}
+            (Some(_), Some(killer_team)) if killer_team != victim_team => *self.scores.entry(killer_team).or_default() += 1,
+            _ => *self.scores.entry(victim_team).or_default() -= 1,
+        }
+    }
+
+    fn scores(&self) -> Vec<ScoreEntry> {
+        [Team::Red, Team::Blue].into_iter()
+            .map(|team| ScoreEntry { owner: ScoreOwner::Team(team), score: self.scores.get(&team).copied().unwrap_or(0) })
+            .collect()
+    }
+
+    fn reset_scores(&mut self) {
+        self.scores.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kill(victim_team: Team, killer_team: Option<Team>) -> PlayerKilled {
+        PlayerKilled { victim: 1, killer: killer_team.map(|_| 2), victim_team: Some(victim_team), killer_team }
+    }
+
+    fn scores(mode: &TeamDeathmatch) -> Vec<i32> {
+        mode.scores().iter().map(|entry| entry.score).collect()
+    }
+
+    #[test]
+    fn kills_score_for_the_killer_team() {
+        let mut mode = TeamDeathmatch::default();
+        mode.player_killed(&kill(Team::Blue, Some(Team::Red)));
+        mode.player_killed(&kill(Team::Blue, Some(Team::Red)));
+        mode.player_killed(&kill(Team::Red, Some(Team::Blue)));
+        assert_eq!(scores(&mode), vec![2, 1]);
+    }
+
+    #[test]
+    fn team_kills_and_suicides_cost_the_victim_team_a_point() {
+        let mut mode = TeamDeathmatch::default();
+        mode.player_killed(&kill(Team::Red, Some(Team::Red)));
+        mode.player_killed(&kill(Team::Blue, None));
+        mode.player_killed(&PlayerKilled { victim: 1, killer: Some(1), victim_team: Some(Team::Blue), killer_team: Some(Team::Blue) });
+        assert_eq!(scores(&mode), vec![-1, -2]);
+
+        mode.reset_scores();
+        assert_eq!(scores(&mode), vec![0, 0]);
+    }
+
+    #[test]
+    fn kills_by_players_who_left_score_for_nobody() {
+        let mut mode = TeamDeathmatch::default();
+        mode.player_killed(&PlayerKilled { victim: 1, killer: Some(2), victim_team: Some(Team::Blue), killer_team: None });
+        assert_eq!(scores(&mode), vec![0, 0]);
+    }
+}
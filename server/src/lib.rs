@@ -1,3 +1,5 @@
+pub mod config;
+pub mod game_mode;
 pub mod system;
 pub mod network;
 pub mod plugin;
\ No newline at end of file
@@ -1,36 +1,68 @@
-use bevy::app::PluginGroup;
-use bevy::prelude::{default, App, AssetPlugin, ImagePlugin, Window, WindowPlugin};
-use bevy::DefaultPlugins;
+use bevy::app::{PluginGroup, ScheduleRunnerPlugin};
+use bevy::log::LogPlugin;
+use bevy::state::app::StatesPlugin;
+use bevy::prelude::{default, App, AssetPlugin, ImagePlugin, TransformPlugin, Window, WindowPlugin};
+use bevy::{DefaultPlugins, MinimalPlugins};
 use bevy_egui::EguiPlugin;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
-use bevy_rapier2d::plugin::{NoUserData, RapierPhysicsPlugin};
+use bevy_rapier2d::plugin::RapierPhysicsPlugin;
 use bevy_rapier2d::prelude::RapierDebugRenderPlugin;
 use bevy_renet2::prelude::RenetServerPlugin;
+use game_core::config::CliArgs;
+use game_core::decor::platform::OneWayPlatformHooks;
+use server::config::ServerSettings;
+use server::network::token::issue_token_command;
 use server::plugin::ServerPlugin;
+use std::time::Duration;
 
 fn main() {
+    let args = CliArgs::from_env();
+    let settings = ServerSettings::load(&args).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+
+    if args.subcommand() == Some("issue-token") {
+        if let Err(e) = issue_token_command(&settings, args.positionals()) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut app = App::new();
-    app.add_plugins(DefaultPlugins
-        .set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Server".to_string(),
-                resolution: (1200.0, 720.0).into(),
-                ..default()
-            }),
+    if settings.headless {
+        app.add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(
+            Duration::from_secs_f64(1.0 / settings.tick_rate),
+        )));
+        app.add_plugins((LogPlugin::default(), TransformPlugin, StatesPlugin));
+        app.add_plugins(AssetPlugin {
+            file_path: settings.asset_path.clone(),
             ..default()
-        }).set(ImagePlugin::default_nearest())
-        .set(AssetPlugin {
-            file_path: "../assets".into(),
-            ..default()
-        }));
+        });
+    } else {
+        app.add_plugins(DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Server".to_string(),
+                    resolution: (1200.0, 720.0).into(),
+                    ..default()
+                }),
+                ..default()
+            }).set(ImagePlugin::default_nearest())
+            .set(AssetPlugin {
+                file_path: settings.asset_path.clone(),
+                ..default()
+            }));
+        app.add_plugins(EguiPlugin::default());
+        app.add_plugins(WorldInspectorPlugin::new());
+        app.add_plugins(RapierDebugRenderPlugin::default());
+    }
 
     app.add_plugins(RenetServerPlugin);
-    app.add_plugins(EguiPlugin::default());
-    app.add_plugins(WorldInspectorPlugin::new());
-    app.add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0));
-    app.add_plugins(RapierDebugRenderPlugin::default());
+    app.add_plugins(RapierPhysicsPlugin::<OneWayPlatformHooks>::pixels_per_meter(100.0));
 
-    app.add_plugins(ServerPlugin);
+    app.add_plugins(ServerPlugin { settings });
 
     app.run();
 }
\ No newline at end of file
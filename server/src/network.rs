@@ -1,2 +1,3 @@
 pub mod system;
-pub mod player;
\ No newline at end of file
+pub mod player;
+pub mod token;
\ No newline at end of file
@@ -1,37 +1,25 @@
-use bevy::asset::Assets;
-use bevy::image::TextureAtlasLayout;
+use crate::system::player_system::AppliedInputSequence;
+use crate::system::team_system::send_team_changed;
 use bevy::log::error;
 use bevy::math::Vec3;
-use bevy::prelude::{ColorMaterial, Commands, Entity, Mesh, Query, Res, ResMut, Transform};
+use bevy::prelude::{Commands, Entity, Has, Query, ResMut, Transform};
 use bevy_renet2::prelude::{ClientId, RenetServer};
-use game_core::network::network::{ServerChannel, ServerMessages};
+use game_core::game_mode::component::Team;
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
 use game_core::player::command::{spawn_player_entity, SpawnPlayerParams};
-use game_core::player::component::{player_physics, spawn_player_sensor, PlayerNetwork};
-use game_core::player::texture::{PlayerTextureType, PlayerTextures};
+use game_core::player::component::{player_physics, spawn_player_sensor, Dead, PlayerNetwork, PlayerWeaponSelected};
+use game_core::player::texture::PlayerTextureType;
 use game_core::weapon::command::spawn_weapon_entity;
-use game_core::weapon::texture::{WeaponTextureType, WeaponTextures};
+use game_core::weapon::texture::WeaponTextureType;
 
-#[allow(clippy::too_many_arguments)]
 pub fn create_player(
     position: Vec3,
     client_id: &ClientId,
     player_texture_type: &PlayerTextureType,
     weapon_texture_type: &WeaponTextureType,
     commands: &mut Commands,
-    player_textures: &mut Res<PlayerTextures>,
-    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<ColorMaterial>>,
-    weapon_textures: &mut Res<WeaponTextures>,
 ) -> Entity {
-    let (pivot, weapon) = spawn_weapon_entity(
-        commands,
-        meshes,
-        materials,
-        texture_atlas_layouts,
-        weapon_textures,
-        weapon_texture_type,
-    );
+    let (pivot, weapon) = spawn_weapon_entity(commands, weapon_texture_type);
     let sensor = spawn_player_sensor(commands);
 
     let player_args = SpawnPlayerParams {
@@ -43,9 +31,9 @@ pub fn create_player(
         client_id: *client_id,
     };
 
-    let player_entity = spawn_player_entity(commands, texture_atlas_layouts, player_textures, player_args);
+    let player_entity = spawn_player_entity(commands, player_args);
 
-    commands.entity(player_entity).insert(player_physics());
+    commands.entity(player_entity).insert((player_physics(), AppliedInputSequence::default()));
     commands.entity(player_entity).add_child(sensor);
     commands.entity(player_entity).add_child(pivot);
     commands.entity(pivot).add_child(weapon);
@@ -53,6 +41,7 @@ pub fn create_player(
 }
 
 pub fn broadcast_player_create(
+    tick: u64,
     player_entity: Entity,
     client_id: &ClientId,
     position: Vec3,
@@ -60,12 +49,15 @@ pub fn broadcast_player_create(
     weapon_texture_entity_type: WeaponTextureType,
     server: &mut ResMut<RenetServer>,
 ) {
-    let message = match bincode::serialize(&ServerMessages::PlayerCreate {
-        id: *client_id,
-        entity: player_entity.to_bits(),
-        translation: position.into(),
-        player_texture_entity_type,
-        weapon_texture_entity_type,
+    let message = match bincode::serialize(&TickedServerMessage {
+        tick,
+        message: ServerMessages::PlayerCreate {
+            id: *client_id,
+            entity: player_entity.to_bits(),
+            translation: position.into(),
+            player_texture_entity_type,
+            weapon_texture_entity_type,
+        },
     }) {
         Ok(msg) => msg,
         Err(e) => {
@@ -77,22 +69,25 @@ pub fn broadcast_player_create(
     server.broadcast_message(ServerChannel::ServerMessages, message);
 }
 
+#[allow(clippy::type_complexity)]
 pub fn send_existing_players_to_client(
-    player_texture_entity_type: PlayerTextureType,
-    weapon_texture_entity_type: WeaponTextureType,
+    tick: u64,
     client_id: &ClientId,
-    players: &mut Query<(Entity, &PlayerNetwork, &Transform)>,
+    players: &mut Query<(Entity, &PlayerNetwork, &Transform, &PlayerTextureType, &PlayerWeaponSelected, Option<&Team>, Has<Dead>)>,
     server: &mut ResMut<RenetServer>,
 ) {
-    for (entity, player, transform) in players.iter() {
+    for (entity, player, transform, player_texture_entity_type, player_weapon_selected, team, _) in players.iter() {
         let translation: [f32; 3] = transform.translation.into();
 
-        let message = match bincode::serialize(&ServerMessages::PlayerCreate {
-            id: player.id,
-            entity: entity.to_bits(),
-            translation,
-            player_texture_entity_type: player_texture_entity_type.clone(),
-            weapon_texture_entity_type: weapon_texture_entity_type.clone(),
+        let message = match bincode::serialize(&TickedServerMessage {
+            tick,
+            message: ServerMessages::PlayerCreate {
+                id: player.id,
+                entity: entity.to_bits(),
+                translation,
+                player_texture_entity_type: player_texture_entity_type.clone(),
+                weapon_texture_entity_type: player_weapon_selected.weapon_texture_type.clone(),
+            },
         }) {
             Ok(msg) => msg,
             Err(e) => {
@@ -101,5 +96,9 @@ pub fn send_existing_players_to_client(
             }
         };
         server.send_message(*client_id, ServerChannel::ServerMessages, message);
+
+        if let Some(team) = team {
+            send_team_changed(tick, player.id, *team, Some(*client_id), server);
+        }
     }
 }
\ No newline at end of file
@@ -1,83 +1,97 @@
 use crate::network::player::{broadcast_player_create, create_player, send_existing_players_to_client};
-use crate::plugin::ServerLobby;
-use bevy::asset::Assets;
-use bevy::image::TextureAtlasLayout;
-use bevy::log::{error, info};
-use bevy::math::Vec3;
-use bevy::prelude::{ColorMaterial, Commands, Entity, EventReader, Mesh, Query, Res, ResMut, Sprite, Transform, With};
+use crate::config::ServerSettings;
+use crate::plugin::{MessageErrors, ServerLobby, ServerTick, SnapshotAcks, SpawnRotation};
+use crate::system::decor_system::send_load_level;
+use crate::system::player_system::AppliedInputSequence;
+use crate::system::respawn_system::select_spawn_point;
+use crate::system::team_system::{change_team, send_team_changed, smaller_team};
+use bevy::log::{error, warn};
+use bevy::prelude::{Commands, Entity, EulerRot, EventReader, Has, Local, Query, Res, ResMut, Transform, With, Without};
+use bevy_egui::EguiContexts;
+use bevy_rapier2d::dynamics::Velocity;
+use bevy_renet2::netcode::NetcodeServerTransport;
 use bevy_renet2::prelude::{ClientId, RenetServer, ServerEvent};
-use game_core::network::network::{ClientChannel, NetworkedEntities, ServerChannel, ServerMessages};
+use game_core::decor::component::SpawnPoint;
+use game_core::decor::level::LevelHandle;
+use game_core::game_mode::component::{PlayerStats, Team};
+use game_core::game_mode::flag::{Flag, FlagCarrier};
+use game_core::network::auth::PlayerUserData;
+use game_core::network::network::{ClientChannel, NetworkedEntities, PlayerCommand, NetworkedEntity, NetworkedFlag, NetworkedMonster, NetworkedPelletVolley, NetworkedPickup, NetworkedPlayer, NetworkedProjectile, ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::network::snapshot::{encode_snapshot, SnapshotAck, SnapshotHistory};
+use game_core::monster::component::{Monster, MonsterType};
+use game_core::pickup::component::{Pickup, PickupCooldown};
 use game_core::player::command::rand_player_texture_entity_type;
-use game_core::player::component::{PlayerInput, PlayerNetwork, PlayerWeaponSelected};
-use game_core::player::texture::{PlayerTextureType, PlayerTextures};
+use game_core::player::component::{Dead, Grounded, JumpCounter, PlayerInput, PlayerName, PlayerNetwork, PlayerWeaponSelected, WeaponInventory};
+use game_core::player::texture::PlayerTextureType;
+use game_core::texture::component::SpriteFrame;
+use game_core::weapon::component::{Pellet, PelletVolley, ProjectileOwner};
 use game_core::weapon::fx_texture::{FxComponent, WeaponFxTextureType};
-use game_core::weapon::texture::WeaponTextures;
+use game_core::weapon::definition::WeaponDefinitions;
+use game_core::weapon::state::WeaponSwap;
+use renet2_visualizer::RenetServerVisualizer;
+use std::collections::HashMap;
 
-#[allow(clippy::too_many_arguments)]
+/// Malformed messages tolerated from a client before it gets disconnected.
+const MAX_MESSAGE_ERRORS: u32 = 10;
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn server_event(
-    mut players: Query<(Entity, &PlayerNetwork, &Transform)>,
-    mut player_textures: Res<PlayerTextures>,
-    mut weapon_textures: Res<WeaponTextures>,
+    mut players: Query<(Entity, &PlayerNetwork, &Transform, &PlayerTextureType, &PlayerWeaponSelected, Option<&Team>, Has<Dead>)>,
     mut server_events: EventReader<ServerEvent>,
     mut commands: Commands,
     mut lobby: ResMut<ServerLobby>,
     mut server: ResMut<RenetServer>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    tick: Res<ServerTick>,
+    mut snapshot_acks: ResMut<SnapshotAcks>,
+    mut message_errors: ResMut<MessageErrors>,
+    mut visualizer: Option<ResMut<RenetServerVisualizer<200>>>,
+    transport: Res<NetcodeServerTransport>,
+    settings: Res<ServerSettings>,
+    mut spawn_rotation: ResMut<SpawnRotation>,
+    spawn_point_query: Query<&Transform, With<SpawnPoint>>,
+    weapon_definitions: Res<WeaponDefinitions>,
+    level_handle: Option<Res<LevelHandle>>,
+    mut pending_connections: Local<Vec<ClientId>>,
 ) {
+    // Players only join once the level, and its spawn points, are there.
+    let level_spawned = level_handle.is_some_and(|level_handle| level_handle.spawned);
+    let mut connected: Vec<ClientId> = if level_spawned { std::mem::take(&mut *pending_connections) } else { Vec::new() };
     for event in server_events.read() {
         match event {
             ServerEvent::ClientConnected { client_id } => {
                 println!("Client {client_id} connected");
-
-                let position = Vec3::new(fastrand::f32() * 800.0 - 400.0, 0.0, 0.0);
-                let player_texture_entity_type = rand_player_texture_entity_type();
-                let weapon_texture_entity_type = PlayerWeaponSelected::default_weapon().weapon_texture_type;
-
-                let player_entity = create_player(
-                    position,
-                    client_id,
-                    &player_texture_entity_type,
-                    &weapon_texture_entity_type,
-                    &mut commands,
-                    &mut player_textures,
-                    &mut texture_atlas_layouts,
-                    &mut meshes,
-                    &mut materials,
-                    &mut weapon_textures,
-                );
-
-                lobby.players.insert(*client_id, player_entity);
-
-                send_existing_players_to_client(
-                    player_texture_entity_type.clone(),
-                    weapon_texture_entity_type.clone(),
-                    client_id,
-                    &mut players,
-                    &mut server,
-                );
-
-                broadcast_player_create(
-                    player_entity,
-                    client_id,
-                    position,
-                    player_texture_entity_type,
-                    weapon_texture_entity_type,
-                    &mut server,
-                )
+                if let Some(visualizer) = visualizer.as_mut() {
+                    visualizer.add_client(*client_id);
+                }
+                if level_spawned {
+                    connected.push(*client_id);
+                } else {
+                    pending_connections.push(*client_id);
+                }
             }
             ServerEvent::ClientDisconnected { client_id, reason } => {
                 println!("Client {client_id} disconnected {reason:?}");
+                pending_connections.retain(|pending| pending != client_id);
+                connected.retain(|connected| connected != client_id);
+                if let Some(visualizer) = visualizer.as_mut() {
+                    visualizer.remove_client(*client_id);
+                }
+                snapshot_acks.0.remove(client_id);
+                message_errors.0.remove(client_id);
+                lobby.stats.remove(client_id);
+                lobby.assailants.remove(client_id);
                 if let Some(entity) = lobby.players.remove(client_id) {
                     commands.entity(entity).despawn();
                 }
 
-                let message = match bincode::serialize(&ServerMessages::PlayerRemove { id: *client_id }) {
+                let message = match bincode::serialize(&TickedServerMessage {
+                    tick: tick.0,
+                    message: ServerMessages::PlayerRemove { id: *client_id },
+                }) {
                     Ok(msg) => msg,
                     Err(e) => {
                         error!("Erreur de sérialisation PlayerRemove: {:?}", e);
-                        return;
+                        continue;
                     }
                 };
 
@@ -85,23 +99,178 @@ pub fn server_event(
             }
         }
     }
+
+    // Teams inserted this frame are not visible to the query yet.
+    let mut joined_teams: Vec<Team> = Vec::new();
+    for client_id in &connected {
+        let player_user_data = transport.user_data(*client_id)
+            .and_then(|user_data| PlayerUserData::from_user_data(&user_data));
+        let player_name = player_user_data.as_ref()
+            .map_or_else(|| format!("Joueur {client_id}"), |user_data| user_data.name.clone());
+        println!("Client {client_id} is {player_name}");
+
+        let spawn_points: Vec<_> = spawn_point_query.iter().map(|transform| transform.translation).collect();
+        let team = settings.game_mode.has_teams()
+            .then(|| smaller_team(players.iter().filter_map(|(.., team, _)| team.copied()).chain(joined_teams.iter().copied())));
+        let enemies: Vec<_> = players.iter()
+            .filter(|(.., player_team, dead)| !dead && (team.is_none() || player_team.copied() != team))
+            .map(|(_, _, transform, ..)| transform.translation)
+            .collect();
+        let position = select_spawn_point(settings.spawn_strategy, &spawn_points, &enemies, &mut spawn_rotation);
+        let player_texture_entity_type = player_user_data
+            .map_or_else(rand_player_texture_entity_type, |user_data| user_data.player_texture_type);
+        let weapon_texture_entity_type = settings.starting_weapons[0].clone();
+
+        let player_entity = create_player(
+            position,
+            client_id,
+            &player_texture_entity_type,
+            &weapon_texture_entity_type,
+            &mut commands,
+        );
+        commands.entity(player_entity).insert((
+            PlayerName(player_name),
+            PlayerWeaponSelected { weapon_texture_type: weapon_texture_entity_type.clone() },
+            WeaponInventory::new(&settings.starting_weapons, &weapon_definitions),
+        ));
+
+        lobby.players.insert(*client_id, player_entity);
+        lobby.stats.insert(*client_id, PlayerStats::default());
+
+        send_load_level(tick.0, *client_id, &settings.level, &mut server);
+
+        send_existing_players_to_client(
+            tick.0,
+            client_id,
+            &mut players,
+            &mut server,
+        );
+
+        broadcast_player_create(
+            tick.0,
+            player_entity,
+            client_id,
+            position,
+            player_texture_entity_type,
+            weapon_texture_entity_type,
+            &mut server,
+        );
+
+        if let Some(team) = team {
+            joined_teams.push(team);
+            commands.entity(player_entity).insert(team);
+            send_team_changed(tick.0, *client_id, team, None, &mut server);
+        }
+    }
 }
 
 pub fn handle_players_input(
     mut server: ResMut<RenetServer>,
+    mut message_errors: ResMut<MessageErrors>,
     mut query: Query<(&PlayerNetwork, &mut PlayerInput)>,
 ) {
     for client_id in server.clients_id() {
         while let Some(message) = server.receive_message(client_id, ClientChannel::Input) {
-            if let Ok(input) = bincode::deserialize::<PlayerInput>(&message) {
-                update_player_input(client_id, input, &mut query);
-            } else {
-                error!("Erreur de désérialisation de PlayerInput pour le client {:?}", client_id);
+            match bincode::deserialize::<PlayerInput>(&message) {
+                Ok(input) if input.aim_direction.is_finite() => update_player_input(client_id, input, &mut query),
+                Ok(input) => {
+                    error!("PlayerInput invalide pour le client {:?}: {:?}", client_id, input);
+                    record_message_error(client_id, &mut message_errors, &mut server);
+                }
+                Err(e) => {
+                    error!("Erreur de désérialisation de PlayerInput pour le client {:?}: {:?}", client_id, e);
+                    record_message_error(client_id, &mut message_errors, &mut server);
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn handle_player_commands(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    mut message_errors: ResMut<MessageErrors>,
+    tick: Res<ServerTick>,
+    settings: Res<ServerSettings>,
+    query: Query<(Entity, &PlayerNetwork, &WeaponInventory, &PlayerWeaponSelected, Option<&WeaponSwap>, Option<&Team>, Has<FlagCarrier>)>,
+) {
+    for client_id in server.clients_id() {
+        while let Some(message) = server.receive_message(client_id, ClientChannel::Command) {
+            let command = match bincode::deserialize::<PlayerCommand>(&message) {
+                Ok(command) => command,
+                Err(e) => {
+                    error!("Erreur de désérialisation de PlayerCommand pour le client {:?}: {:?}", client_id, e);
+                    record_message_error(client_id, &mut message_errors, &mut server);
+                    continue;
+                }
+            };
+
+            let Some((entity, _, inventory, selected, swap, team, flag_carrier)) = query.iter().find(|(_, player, ..)| player.id == client_id) else { continue };
+            let current = swap.map_or(&selected.weapon_texture_type, |swap| &swap.weapon);
+            let weapon = match command {
+                PlayerCommand::SelectWeapon { slot } => inventory.slot(slot as usize),
+                PlayerCommand::CycleWeapon { offset } => inventory.cycle(current, offset),
+                PlayerCommand::ChangeTeam { team: new_team } => {
+                    let team_size = |team: Team| query.iter().filter(|(.., player_team, _)| *player_team == Some(&team)).count();
+                    match team {
+                        Some(team) if settings.game_mode.has_teams() && new_team != *team && team_size(new_team) < team_size(*team) => {
+                            change_team(&mut commands, entity, client_id, new_team, tick.0, &mut server);
+                        }
+                        _ => warn!("Client {:?}: changement d'équipe refusé ({:?})", client_id, command),
+                    }
+                    continue;
+                }
+            };
+
+            match weapon {
+                Some(_) if flag_carrier => warn!("Client {:?}: changement d'arme refusé au porteur du drapeau", client_id),
+                Some(weapon) if weapon != current => {
+                    commands.entity(entity).insert(WeaponSwap::new(weapon.clone()));
+                }
+                Some(_) => {}
+                None => warn!("Client {:?}: arme absente de l'inventaire ({:?})", client_id, command),
+            }
+        }
+    }
+}
+
+pub fn handle_snapshot_acks(
+    mut server: ResMut<RenetServer>,
+    mut snapshot_acks: ResMut<SnapshotAcks>,
+    mut message_errors: ResMut<MessageErrors>,
+) {
+    for client_id in server.clients_id() {
+        while let Some(message) = server.receive_message(client_id, ClientChannel::SnapshotAck) {
+            match bincode::deserialize::<SnapshotAck>(&message) {
+                Ok(ack) => {
+                    let acked_tick = snapshot_acks.0.entry(client_id).or_insert(ack.tick);
+                    *acked_tick = (*acked_tick).max(ack.tick);
+                }
+                Err(e) => {
+                    error!("Erreur de désérialisation de SnapshotAck pour le client {:?}: {:?}", client_id, e);
+                    record_message_error(client_id, &mut message_errors, &mut server);
+                }
             }
         }
     }
 }
 
+fn record_message_error(
+    client_id: ClientId,
+    message_errors: &mut MessageErrors,
+    server: &mut RenetServer,
+) {
+    let errors = message_errors.0.entry(client_id).or_default();
+    *errors += 1;
+    warn!("Client {:?}: {} message(s) invalide(s)", client_id, errors);
+
+    if *errors >= MAX_MESSAGE_ERRORS && server.is_connected(client_id) {
+        error!("Client {:?} déconnecté après {} messages invalides", client_id, errors);
+        server.disconnect(client_id);
+    }
+}
+
 fn update_player_input(
     client_id: ClientId,
     input: PlayerInput,
@@ -109,55 +278,193 @@ fn update_player_input(
 ) {
     for (player_net, mut player_input) in players {
         if player_net.id == client_id {
-            *player_input = input;
+            merge_player_input(&mut player_input, input);
         }
     }
 }
 
+/// Keeps the newest input, without losing a jump or reload pressed in an input the server has not applied yet.
+/// Late inputs are dropped; a resend of the current one only updates the aim.
+fn merge_player_input(current: &mut PlayerInput, input: PlayerInput) {
+    if (input.sequence.wrapping_sub(current.sequence) as i32) > 0 {
+        *current = PlayerInput {
+            jump: input.jump || current.jump,
+            reload: input.reload || current.reload,
+            ..input
+        };
+    } else if input.sequence == current.sequence {
+        current.aim_direction = input.aim_direction;
+    }
+}
+
+pub fn advance_server_tick(mut tick: ResMut<ServerTick>) {
+    tick.0 += 1;
+}
+
 #[allow(clippy::complexity)]
 pub fn server_network_sync(
     mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    snapshot_acks: Res<SnapshotAcks>,
+    mut snapshot_history: ResMut<SnapshotHistory>,
     player_query: Query<(
         Entity,
         &Transform,
-        &Sprite,
+        &SpriteFrame,
         &PlayerTextureType,
         &PlayerWeaponSelected,
-        &PlayerInput
+        &PlayerInput,
+        &AppliedInputSequence,
+        &Velocity,
+        &Grounded,
+        &JumpCounter,
     ), With<PlayerNetwork>>,
-    projectile_query: Query<(Entity, &Transform, &Sprite, &WeaponFxTextureType), With<FxComponent>>,
+    projectile_query: Query<(Entity, &Transform, &SpriteFrame, &WeaponFxTextureType, &ProjectileOwner), (With<FxComponent>, Without<Pellet>)>,
+    volley_query: Query<(Entity, &PelletVolley, &WeaponFxTextureType, &ProjectileOwner)>,
+    pellet_query: Query<&Pellet>,
+    pickup_query: Query<(Entity, &Transform, &Pickup), Without<PickupCooldown>>,
+    monster_query: Query<(Entity, &Transform, &SpriteFrame, &MonsterType), With<Monster>>,
+    flag_query: Query<(Entity, &Transform, &Flag)>,
 ) {
-    let mut networked_entities = NetworkedEntities::default();
-    for (entity, transform, sprite, texture_entity_type, player_weapon_selected, player_input) in player_query.iter() {
-        networked_entities.entities.push(entity.to_bits());
-        networked_entities.translations.push(transform.translation.into());
-
-        if let Some(texture) = &sprite.texture_atlas {
-            networked_entities.sprite_index.push(texture.index);
-            networked_entities.sprite_flip_x.push(sprite.flip_x);
-        }
+    let mut networked_entities = NetworkedEntities {
+        tick: tick.0,
+        ..Default::default()
+    };
+    for (entity, transform, sprite_frame, texture_entity_type, player_weapon_selected, player_input, applied_input, velocity, grounded, jump_counter) in player_query.iter() {
+        networked_entities.entities.push(NetworkedEntity::Player(NetworkedPlayer {
+            entity: entity.to_bits(),
+            translation: transform.translation.into(),
+            sprite_index: sprite_frame.index,
+            sprite_flip_x: sprite_frame.flip_x,
+            player_texture_entity_type: texture_entity_type.clone(),
+            weapon_texture_entity_type: player_weapon_selected.clone(),
+            aim_direction: player_input.aim_direction,
+            velocity: velocity.linvel.into(),
+            grounded: grounded.0,
+            jumps_left: jump_counter.jumps_left,
+            last_input_sequence: applied_input.0,
+        }));
+    }
 
-        networked_entities.player_texture_entity_type.push(texture_entity_type.clone());
-        networked_entities.weapon_texture_entity_type.push(player_weapon_selected.clone());
-        networked_entities.player_aim_direction.push(player_input.aim_direction);
+    for (projectile_entity, transform, sprite_frame, weapon_fx_texture_type, owner) in projectile_query.iter() {
+        networked_entities.entities.push(NetworkedEntity::Projectile(NetworkedProjectile {
+            entity: projectile_entity.to_bits(),
+            owner: owner.0,
+            translation: transform.translation.into(),
+            aim_direction: transform.rotation.to_euler(EulerRot::ZYX).0,
+            sprite_index: sprite_frame.index,
+            sprite_flip_y: sprite_frame.flip_y,
+            weapon_fx_texture_type: weapon_fx_texture_type.clone(),
+        }));
     }
 
-    for (projectile_entity, transform, sprite, weapon_texture_type) in projectile_query.iter() {
-        info!("Projectile entity: {:?}", projectile_entity);
-        networked_entities.projectile_entities.push(projectile_entity.to_bits());
-        networked_entities.projectile_translations.push(transform.translation.into());
+    let mut alive_pellets: HashMap<Entity, u32> = HashMap::new();
+    for pellet in pellet_query.iter() {
+        *alive_pellets.entry(pellet.volley).or_default() |= 1 << pellet.index;
+    }
 
-        if let Some(texture) = &sprite.texture_atlas {
-            networked_entities.projectile_sprite_index.push(texture.index);
-            networked_entities.projectile_sprite_flip_y.push(sprite.flip_y);
-        }
+    for (volley_entity, volley, weapon_fx_texture_type, owner) in volley_query.iter() {
+        let Some(alive_pellets) = alive_pellets.get(&volley_entity) else { continue };
+        networked_entities.entities.push(NetworkedEntity::PelletVolley(NetworkedPelletVolley {
+            entity: volley_entity.to_bits(),
+            owner: owner.0,
+            origin: volley.origin.into(),
+            aim_direction: volley.aim_direction,
+            spread_degrees: volley.spread_degrees,
+            seed: volley.seed,
+            pellet_count: volley.pellet_count,
+            speed: volley.speed,
+            fired_tick: volley.fired_tick,
+            alive_pellets: *alive_pellets,
+            weapon_fx_texture_type: weapon_fx_texture_type.clone(),
+        }));
+    }
+
+    for (monster_entity, transform, sprite_frame, monster_type) in monster_query.iter() {
+        networked_entities.entities.push(NetworkedEntity::Monster(NetworkedMonster {
+            entity: monster_entity.to_bits(),
+            translation: transform.translation.into(),
+            sprite_index: sprite_frame.index,
+            sprite_flip_x: sprite_frame.flip_x,
+            monster_type: *monster_type,
+        }));
+    }
+
+    for (pickup_entity, transform, pickup) in pickup_query.iter() {
+        networked_entities.entities.push(NetworkedEntity::Pickup(NetworkedPickup {
+            entity: pickup_entity.to_bits(),
+            translation: transform.translation.into(),
+            pickup: pickup.clone(),
+        }));
+    }
 
-        networked_entities.weapon_fx_texture_type = weapon_texture_type.clone();
+    for (flag_entity, transform, flag) in flag_query.iter() {
+        networked_entities.entities.push(NetworkedEntity::Flag(NetworkedFlag {
+            entity: flag_entity.to_bits(),
+            translation: transform.translation.into(),
+            team: flag.team,
+        }));
     }
 
-    if !networked_entities.entities.is_empty() {
-        let sync_message = bincode::serialize(&networked_entities).unwrap();
-        server.broadcast_message(ServerChannel::NetworkedEntities, sync_message);
+    if networked_entities.entities.is_empty() {
+        return;
     }
+
+    for client_id in server.clients_id() {
+        let baseline = snapshot_acks.0.get(&client_id).and_then(|tick| snapshot_history.get(*tick));
+        let snapshot_delta = encode_snapshot(&networked_entities, baseline);
+        let sync_message = match bincode::serialize(&snapshot_delta) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Erreur de sérialisation du snapshot {} pour le client {:?}: {:?}", networked_entities.tick, client_id, e);
+                continue;
+            }
+        };
+        server.send_message(client_id, ServerChannel::NetworkedEntities, sync_message);
+    }
+
+    snapshot_history.push(networked_entities);
 }
 
+pub fn update_visualizer_system(
+    mut egui_contexts: EguiContexts,
+    mut visualizer: ResMut<RenetServerVisualizer<200>>,
+    server: Res<RenetServer>,
+) {
+    visualizer.update(&server);
+    if let Ok(ctx) = egui_contexts.ctx_mut() {
+        visualizer.show_window(ctx);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(sequence: u32, jump: bool) -> PlayerInput {
+        PlayerInput { sequence, jump, right: true, ..Default::default() }
+    }
+
+    #[test]
+    fn jump_pressed_in_an_overwritten_input_is_kept() {
+        let mut current = input(1, false);
+        merge_player_input(&mut current, input(2, true));
+        merge_player_input(&mut current, PlayerInput { reload: true, ..input(3, false) });
+
+        assert_eq!((current.sequence, current.jump, current.reload), (3, true, true));
+    }
+
+    #[test]
+    fn late_inputs_are_dropped_and_resends_only_aim() {
+        let mut current = input(5, false);
+        merge_player_input(&mut current, input(4, true));
+        assert!(!current.jump);
+
+        merge_player_input(&mut current, PlayerInput { aim_direction: 1.0, ..input(5, true) });
+        assert_eq!((current.aim_direction, current.jump), (1.0, false));
+
+        merge_player_input(&mut current, input(u32::MAX, false));
+        assert_eq!(current.sequence, 5);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::config::ServerSettings;
+use game_core::network::auth::{issue_connect_token, load_or_create_private_key, parse_player_texture_type, write_connect_token, PlayerUserData, CONNECT_TOKEN_PATH};
+use game_core::network::utils::get_current_time;
+
+/// `server issue-token <name> [rick1|rick2] [output]`: mints a connect token signed with the server private key.
+pub fn issue_token_command(settings: &ServerSettings, args: &[String]) -> Result<(), String> {
+    let name = args.first().ok_or("usage: server issue-token <nom> [rick1|rick2] [fichier]")?;
+    let player_texture_type = match args.get(1) {
+        Some(skin) => parse_player_texture_type(skin).ok_or_else(|| format!("skin inconnu: {skin}"))?,
+        None => Default::default(),
+    };
+    let output = args.get(2).map_or(CONNECT_TOKEN_PATH, String::as_str);
+
+    let private_key = load_or_create_private_key(&settings.private_key_path)
+        .map_err(|e| format!("impossible de charger la clé privée {}: {e}", settings.private_key_path))?;
+    let player_user_data = PlayerUserData {
+        name: name.clone(),
+        player_texture_type,
+    };
+
+    let connect_token = issue_connect_token(get_current_time(), settings.protocol_id, settings.public_addr(), &player_user_data, &private_key)
+        .map_err(|e| format!("échec de la création du jeton: {e}"))?;
+    write_connect_token(&connect_token, output)
+        .map_err(|e| format!("impossible d'écrire {output}: {e}"))?;
+
+    println!("Jeton de connexion du client {} écrit dans {output}", connect_token.client_id);
+    Ok(())
+}
@@ -1,79 +1,252 @@
-use crate::network::system::{handle_players_input, server_event, server_network_sync};
-use crate::system::decor_system::setup_camera;
-use crate::system::player_system::{player_jump_control, player_move, player_shoot};
+use crate::config::{GameModeKind, ServerSettings};
+use crate::network::system::{advance_server_tick, handle_player_commands, handle_players_input, handle_snapshot_acks, server_event, server_network_sync, update_visualizer_system};
+use crate::system::decor_system::{load_server_level, setup_camera, setup_level};
+use crate::system::monster_system::{monster_ai, monster_death, spawn_monsters};
+use crate::system::pickup_system::{collect_pickups, respawn_pickups};
+use crate::system::player_system::{finish_weapon_swaps, player_jump_control, player_move, player_shoot};
+use crate::system::projectile_system::{despawn_spent_volleys, grenade_detonation, projectile_hit};
+use crate::system::flag_system::{carry_flags, reset_flags, touch_flags, FlagCaptured};
+use crate::system::match_system::{advance_match, enter_match, enter_results, enter_warmup, score_captures, score_kills, send_match_state, track_match_players, MatchClock};
+use crate::system::respawn_system::{player_death, player_respawn, PlayerKilled};
+use crate::system::scoreboard_system::send_scoreboard;
+use crate::system::team_system::balance_teams;
+use crate::system::wave_system::{run_waves, WaveState};
 use bevy::app::{App, FixedUpdate, Plugin, Startup, Update};
-use bevy::log::error;
-use bevy::prelude::{Entity, Events, Resource};
+use bevy::state::app::AppExtStates;
+use bevy::log::{error, warn};
+use bevy::prelude::{in_state, AssetApp, not, Entity, Events, Fixed, IntoScheduleConfigs, OnEnter, Resource, Time};
+use bevy_egui::EguiPrimaryContextPass;
 use bevy_rapier2d::pipeline::CollisionEvent;
 use bevy_renet2::netcode::{NetcodeServerPlugin, NetcodeServerTransport, ServerAuthentication, ServerSetupConfig};
 use bevy_renet2::prelude::{ClientId, RenetServer};
-use game_core::decor::system::setup_ground;
-use game_core::network::network::{connection_config, PROTOCOL_ID};
+use game_core::game_mode::component::{MatchPhase, PlayerStats};
+use game_core::decor::level::{LevelDefinition, LevelLoader};
+use game_core::decor::tiled::TiledLoader;
+use game_core::decor::system::default_kill_volume;
+use game_core::network::auth::load_or_create_private_key;
+use game_core::network::network::connection_config;
+use game_core::network::snapshot::SnapshotHistory;
 use game_core::network::utils::{get_current_time, get_native_socket, get_socket};
+use game_core::monster::animation::animate_monsters;
+use game_core::monster::wave::WaveDefinitions;
 use game_core::player::animation::animate_players;
-use game_core::texture::system::{load_player_textures, load_weapon_fx_textures, load_weapon_textures};
+use game_core::player::system::expire_invulnerability;
 use game_core::weapon::animation::animate_weapons;
-use game_core::weapon::command::despawn_weapon_fx_out_of_screen_system;
+use game_core::weapon::command::despawn_weapon_fx_out_of_range_system;
+use game_core::weapon::definition::WeaponDefinitions;
 use renet2_visualizer::RenetServerVisualizer;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Default, Resource)]
 pub struct ServerLobby {
     pub players: HashMap<ClientId, Entity>,
+    pub stats: HashMap<ClientId, PlayerStats>,
+    /// Players that damaged each player since it last spawned, for assists.
+    pub assailants: HashMap<ClientId, HashSet<ClientId>>,
+}
+
+impl ServerLobby {
+    /// `victim` is `None` for monsters, which give no assists.
+    pub fn record_damage(&mut self, attacker: ClientId, victim: Option<ClientId>, amount: u32) {
+        if let Some(stats) = self.stats.get_mut(&attacker) {
+            stats.damage_dealt += amount;
+        }
+        if let Some(victim) = victim {
+            self.assailants.entry(victim).or_default().insert(attacker);
+        }
+    }
+
+    /// Counts the death, the kill unless it was a suicide or a team kill, and returns the players credited with an assist.
+    pub fn record_death(&mut self, victim: ClientId, killer: Option<ClientId>, team_kill: bool) -> Vec<ClientId> {
+        if let Some(stats) = self.stats.get_mut(&victim) {
+            stats.deaths += 1;
+        }
+        if let Some(killer) = killer.filter(|killer| *killer != victim && !team_kill)
+            && let Some(stats) = self.stats.get_mut(&killer)
+        {
+            stats.kills += 1;
+        }
+
+        let mut assists: Vec<ClientId> = self.assailants.remove(&victim)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|assailant| Some(*assailant) != killer && *assailant != victim)
+            .collect();
+        assists.sort_unstable();
+        for assistant in &assists {
+            if let Some(stats) = self.stats.get_mut(assistant) {
+                stats.assists += 1;
+            }
+        }
+        assists
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats.values_mut().for_each(|stats| *stats = PlayerStats::default());
+        self.assailants.clear();
+    }
+}
+
+/// Last snapshot tick acknowledged by each client, used as its delta baseline.
+#[derive(Debug, Default, Resource)]
+pub struct SnapshotAcks(pub HashMap<ClientId, u64>);
+
+/// Malformed messages received from each connected client.
+#[derive(Debug, Default, Resource)]
+pub struct MessageErrors(pub HashMap<ClientId, u32>);
+
+/// Next spawn point index for `SpawnStrategy::RoundRobin`.
+#[derive(Debug, Default, Resource)]
+pub struct SpawnRotation(pub usize);
+
+/// Number of fixed simulation steps since the server started.
+#[derive(Debug, Default, Resource)]
+pub struct ServerTick(pub u64);
+
+pub struct ServerPlugin {
+    pub settings: ServerSettings,
 }
-pub struct ServerPlugin;
 
 impl Plugin for ServerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ServerLobby::default());
-        app.insert_resource(RenetServerVisualizer::<200>::default());
+        app.insert_resource(ServerTick::default());
+        app.insert_resource(SnapshotAcks::default());
+        app.insert_resource(MessageErrors::default());
+        app.insert_resource(SnapshotHistory::default());
+        app.insert_resource(SpawnRotation::default());
+        app.insert_resource(default_kill_volume());
+        app.init_asset::<LevelDefinition>();
+        app.init_asset_loader::<LevelLoader>();
+        app.init_asset_loader::<TiledLoader>();
+
+        let weapon_definitions = WeaponDefinitions::load(&self.settings.asset_path).unwrap_or_else(|e| {
+            error!("Impossible de charger les définitions d'armes");
+            panic!("reason : {e}");
+        });
+        app.insert_resource(weapon_definitions);
+        app.insert_resource(Time::<Fixed>::from_hz(self.settings.tick_rate));
+        app.insert_resource(self.settings.clone());
         app.init_resource::<Events<CollisionEvent>>();
+        app.add_event::<PlayerKilled>();
 
-        add_netcode_network(app);
+        add_netcode_network(app, &self.settings);
 
         app.add_systems(Update, (
             server_event,
             animate_players,
             animate_weapons,
+            animate_monsters,
             player_jump_control,
             player_move,
-            player_shoot,
-            despawn_weapon_fx_out_of_screen_system
+            player_shoot.run_if(not(in_state(MatchPhase::Ended))),
+            finish_weapon_swaps,
+            projectile_hit,
+            grenade_detonation,
+            collect_pickups,
+            respawn_pickups,
+            (
+                monster_ai,
+                monster_death.after(projectile_hit).after(grenade_detonation),
+            ),
+            player_death.after(projectile_hit).after(grenade_detonation),
+            player_respawn,
+            send_scoreboard.after(player_death).after(player_respawn),
+            expire_invulnerability,
+            despawn_weapon_fx_out_of_range_system,
+            despawn_spent_volleys,
         ));
 
+        if let Some(game_mode) = self.settings.game_mode.create() {
+            app.insert_resource(game_mode);
+            app.insert_resource(MatchClock::default());
+            app.init_state::<MatchPhase>();
+            app.add_systems(OnEnter(MatchPhase::Warmup), enter_warmup);
+            app.add_systems(OnEnter(MatchPhase::InProgress), enter_match);
+            app.add_systems(OnEnter(MatchPhase::Ended), enter_results);
+            app.add_systems(Update, (
+                track_match_players,
+                score_kills.after(player_death),
+                advance_match,
+                send_match_state,
+            ).chain());
+        }
+
+        if self.settings.game_mode.has_teams() {
+            app.add_systems(Update, balance_teams.after(server_event));
+        }
+
+        if self.settings.game_mode == GameModeKind::CaptureTheFlag {
+            app.add_event::<FlagCaptured>();
+            app.add_systems(OnEnter(MatchPhase::InProgress), reset_flags);
+            app.add_systems(Update, (
+                touch_flags,
+                carry_flags.after(player_death),
+                score_captures.after(touch_flags).before(advance_match),
+            ));
+        }
+
+        match self.settings.game_mode {
+            GameModeKind::Deathmatch | GameModeKind::TeamDeathmatch | GameModeKind::CaptureTheFlag => {
+                app.add_systems(Update, spawn_monsters);
+            }
+            GameModeKind::Waves => {
+                let wave_definitions = WaveDefinitions::load(&self.settings.asset_path).unwrap_or_else(|e| {
+                    error!("Impossible de charger les définitions de vagues");
+                    panic!("reason : {e}");
+                });
+                app.insert_resource(WaveState::new(&wave_definitions));
+                app.insert_resource(wave_definitions);
+                app.add_systems(Update, run_waves);
+            }
+        }
+
         app.add_systems(FixedUpdate, (
-            server_network_sync,
+            (advance_server_tick, server_network_sync).chain(),
             handle_players_input,
+            handle_player_commands,
+            handle_snapshot_acks,
         ));
 
+        app.add_systems(Startup, load_server_level);
+        app.add_systems(Update, setup_level.before(server_event));
 
-        app.add_systems(Startup, (
-            load_player_textures,
-            load_weapon_textures,
-            load_weapon_fx_textures,
-            setup_camera,
-            setup_ground,
-        ));
+        if !self.settings.headless {
+            app.insert_resource(RenetServerVisualizer::<200>::default());
+            app.add_systems(EguiPrimaryContextPass, update_visualizer_system);
+            app.add_systems(Startup, setup_camera);
+        }
     }
 }
 
-fn add_netcode_network(app: &mut App) {
+fn add_netcode_network(app: &mut App, settings: &ServerSettings) {
     app.add_plugins(NetcodeServerPlugin);
 
     let server = RenetServer::new(connection_config());
 
-    let public_addr = "127.0.0.1:5000".parse().expect("Échec du parsing de l'adresse publique");
+    let public_addr = settings.public_addr();
+
+    let authentication = if settings.unsecure {
+        warn!("Serveur lancé en mode non sécurisé");
+        ServerAuthentication::Unsecure
+    } else {
+        let private_key = load_or_create_private_key(&settings.private_key_path).unwrap_or_else(|e| {
+            error!("Impossible de charger la clé privée {}", settings.private_key_path);
+            panic!("reason : {e}");
+        });
+        ServerAuthentication::Secure { private_key }
+    };
 
-    let socket = get_socket(public_addr);
+    let socket = get_socket(settings.bind_addr());
 
     let current_time: std::time::Duration = get_current_time();
 
     let server_config = ServerSetupConfig {
         current_time,
-        max_clients: 64,
-        protocol_id: PROTOCOL_ID,
+        max_clients: settings.max_clients,
+        protocol_id: settings.protocol_id,
         socket_addresses: vec![vec![public_addr]],
-        authentication: ServerAuthentication::Unsecure,
+        authentication,
     };
 
     let native_socket = get_native_socket(socket);
@@ -85,4 +258,65 @@ fn add_netcode_network(app: &mut App) {
 
     app.insert_resource(server);
     app.insert_resource(transport);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lobby(players: &[ClientId]) -> ServerLobby {
+        let mut lobby = ServerLobby::default();
+        for player in players {
+            lobby.stats.insert(*player, PlayerStats::default());
+        }
+        lobby
+    }
+
+    #[test]
+    fn kills_credit_the_killer_and_other_assailants_assist() {
+        let mut lobby = lobby(&[1, 2, 3]);
+        lobby.record_damage(1, Some(3), 40);
+        lobby.record_damage(2, Some(3), 60);
+
+        let assists = lobby.record_death(3, Some(2), false);
+
+        assert_eq!(assists, vec![1]);
+        assert_eq!((lobby.stats[&1].assists, lobby.stats[&1].damage_dealt), (1, 40));
+        assert_eq!((lobby.stats[&2].kills, lobby.stats[&2].damage_dealt), (1, 60));
+        assert_eq!(lobby.stats[&3].deaths, 1);
+        assert!(!lobby.assailants.contains_key(&3));
+    }
+
+    #[test]
+    fn deaths_without_killer_only_give_assists() {
+        let mut lobby = lobby(&[1, 2]);
+        lobby.record_damage(1, Some(2), 30);
+
+        let assists = lobby.record_death(2, None, false);
+
+        assert_eq!(assists, vec![1]);
+        assert_eq!((lobby.stats[&1].kills, lobby.stats[&1].assists), (0, 1));
+    }
+
+    #[test]
+    fn suicides_and_team_kills_give_no_kill() {
+        let mut lobby = lobby(&[1, 2]);
+        lobby.record_damage(1, Some(1), 20);
+        assert!(lobby.record_death(1, Some(1), false).is_empty());
+
+        lobby.record_damage(1, Some(2), 20);
+        assert!(lobby.record_death(2, Some(1), true).is_empty());
+
+        assert_eq!((lobby.stats[&1].kills, lobby.stats[&1].deaths), (0, 1));
+        assert_eq!(lobby.stats[&2].deaths, 1);
+    }
+
+    #[test]
+    fn monster_damage_counts_without_assailant() {
+        let mut lobby = lobby(&[1]);
+        lobby.record_damage(1, None, 25);
+
+        assert_eq!(lobby.stats[&1].damage_dealt, 25);
+        assert!(lobby.assailants.is_empty());
+    }
+}
@@ -1,2 +1,11 @@
 pub mod player_system;
 pub mod decor_system;
+pub mod projectile_system;
+pub mod respawn_system;
+pub mod pickup_system;
+pub mod monster_system;
+pub mod wave_system;
+pub mod match_system;
+pub mod team_system;
+pub mod flag_system;
+pub mod scoreboard_system;
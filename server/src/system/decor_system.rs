@@ -1,5 +1,117 @@
-use bevy::prelude::{Camera2d, Commands};
+use crate::config::{GameModeKind, ServerSettings};
+use bevy::asset::{AssetServer, Assets, RecursiveDependencyLoadState};
+use bevy::log::{error, info};
+use bevy::prelude::{Camera2d, Commands, Res, ResMut};
+use bevy_renet2::prelude::{ClientId, RenetServer};
+use game_core::decor::level::{LevelDefinition, LevelHandle};
+use game_core::decor::system::{load_level, spawn_level_colliders, spawn_level_flags, spawn_level_monster_spawners, spawn_level_pickups};
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
 
 pub fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
+
+pub fn load_server_level(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<ServerSettings>,
+) {
+    commands.insert_resource(load_level(&asset_server, &settings.level));
+}
+
+/// Spawns the level once its file is loaded; the server cannot run without it.
+pub fn setup_level(
+    mut commands: Commands,
+    mut level_handle: ResMut<LevelHandle>,
+    levels: Res<Assets<LevelDefinition>>,
+    asset_server: Res<AssetServer>,
+    settings: Res<ServerSettings>,
+) {
+    if level_handle.spawned {
+        return;
+    }
+
+    let Some(level) = levels.get(&level_handle.handle) else {
+        if let Some(RecursiveDependencyLoadState::Failed(e)) = asset_server.get_recursive_dependency_load_state(&level_handle.handle) {
+            error!("Impossible de charger le niveau {}", level_handle.name);
+            panic!("reason : {e}");
+        }
+        return;
+    };
+
+    if let Err(e) = check_level_for_mode(level, settings.game_mode) {
+        error!("Le niveau {} ne convient pas au mode {:?}", level_handle.name, settings.game_mode);
+        panic!("reason : {e}");
+    }
+
+    spawn_level_colliders(&mut commands, level);
+    spawn_level_pickups(&mut commands, level);
+    spawn_level_monster_spawners(&mut commands, level);
+    if settings.game_mode == GameModeKind::CaptureTheFlag {
+        spawn_level_flags(&mut commands, level);
+    }
+    level_handle.spawned = true;
+    info!("Level {} loaded: {} platforms, {} spawn points", level_handle.name, level.platforms.len(), level.spawn_points.len());
+}
+
+/// Waves are spawned at the level monster spawners; without any, a wave would never end.
+fn check_level_for_mode(level: &LevelDefinition, game_mode: GameModeKind) -> Result<(), String> {
+    if game_mode == GameModeKind::Waves && level.monster_spawners.is_empty() {
+        return Err("le mode waves demande au moins un monster_spawners dans le niveau".to_string());
+    }
+    Ok(())
+}
+
+pub fn send_load_level(
+    tick: u64,
+    client_id: ClientId,
+    level: &str,
+    server: &mut RenetServer,
+) {
+    let message = match bincode::serialize(&TickedServerMessage {
+        tick,
+        message: ServerMessages::LoadLevel { name: level.to_string() },
+    }) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Erreur de sérialisation LoadLevel: {:?}", e);
+            return;
+        }
+    };
+
+    server.send_message(client_id, ServerChannel::ServerMessages, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::Vec2;
+    use game_core::decor::component::Area;
+    use game_core::decor::level::PlatformDefinition;
+    use game_core::monster::component::{MonsterSpawn, MonsterType};
+
+    fn level(monster_spawners: Vec<MonsterSpawn>) -> LevelDefinition {
+        LevelDefinition {
+            platforms: vec![PlatformDefinition { position: Vec2::ZERO, size: Vec2::new(800.0, 25.0), one_way: false, visible: true }],
+            spawn_points: vec![Vec2::new(0.0, 100.0)],
+            pickups: Vec::new(),
+            monster_spawners,
+            flag_bases: Vec::new(),
+            bounds: Area { min: Vec2::splat(-1000.0), max: Vec2::splat(1000.0) },
+            kill_zones: Vec::new(),
+            backgrounds: Vec::new(),
+            tile_sheets: Vec::new(),
+            tile_layers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn waves_need_a_monster_spawner() {
+        let without_spawners = level(Vec::new());
+        assert!(check_level_for_mode(&without_spawners, GameModeKind::Waves).is_err());
+        assert!(check_level_for_mode(&without_spawners, GameModeKind::Deathmatch).is_ok());
+
+        let with_spawner = level(vec![MonsterSpawn { monster_type: MonsterType::Blob, position: Vec2::ZERO, respawn_secs: 10.0 }]);
+        assert!(check_level_for_mode(&with_spawner, GameModeKind::Waves).is_ok());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::plugin::ServerTick;
+use bevy::log::error;
+use bevy::prelude::{Commands, Entity, Event, EventReader, EventWriter, Has, Mut, Query, Res, ResMut, Time, Transform, With, Without};
+use bevy_rapier2d::geometry::ColliderDisabled;
+use bevy_rapier2d::pipeline::CollisionEvent;
+use bevy_renet2::prelude::RenetServer;
+use game_core::decor::component::KillVolume;
+use game_core::game_mode::component::Team;
+use game_core::game_mode::flag::{Flag, FlagCarrier, FlagEvent, FlagHome, FlagState, FLAG_CARRIER_WEAPON, FLAG_CARRY_OFFSET, FLAG_RETURN_SECS};
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::player::component::{Dead, PlayerNetwork, PlayerWeaponSelected};
+use game_core::weapon::state::WeaponSwap;
+
+/// The flag of `team` was captured, i.e. the other team scores.
+#[derive(Debug, Event)]
+pub struct FlagCaptured {
+    pub team: Team,
+}
+
+/// Enemies take a flag, defenders send a dropped one home, carriers capture by touching their own flag at its base.
+#[allow(clippy::type_complexity)]
+pub fn touch_flags(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut flag_captured: EventWriter<FlagCaptured>,
+    mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    mut flag_query: Query<(Entity, &Flag, &FlagHome, &mut FlagState, &mut Transform)>,
+    player_query: Query<(Entity, &PlayerNetwork, &Team, &PlayerWeaponSelected, Option<&FlagCarrier>), Without<Dead>>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+
+        let (flag_entity, player_entity) = match (flag_query.contains(*e1), flag_query.contains(*e2)) {
+            (true, false) => (*e1, *e2),
+            (false, true) => (*e2, *e1),
+            _ => continue,
+        };
+        let Ok((player_entity, player_network, team, selected, carrier)) = player_query.get(player_entity) else { continue };
+        let Ok((_, flag, _, state, _)) = flag_query.get(flag_entity) else { continue };
+
+        if flag.team == *team && matches!(state, FlagState::AtBase) && let Some(carrier) = carrier {
+            commands.entity(player_entity).remove::<FlagCarrier>();
+            if let Some((captured_entity, _, captured_home, mut captured_state, mut captured_transform)) = flag_query.iter_mut()
+                .find(|(_, flag, ..)| flag.team == carrier.team)
+            {
+                return_flag(&mut captured_state, &mut captured_transform, captured_home);
+                commands.entity(captured_entity).remove::<ColliderDisabled>();
+            }
+            flag_captured.write(FlagCaptured { team: carrier.team });
+            send_flag_changed(tick.0, carrier.team, FlagEvent::Captured { by: player_network.id }, &mut server);
+            continue;
+        }
+
+        let Ok((_, flag, home, mut state, mut transform)) = flag_query.get_mut(flag_entity) else { continue };
+
+        let flag_event = match *state {
+            FlagState::AtBase | FlagState::Dropped(_) if flag.team != *team => {
+                *state = FlagState::Carried { player: player_entity, id: player_network.id };
+                commands.entity(flag_entity).insert(ColliderDisabled);
+                commands.entity(player_entity).insert(FlagCarrier { team: flag.team });
+                if selected.weapon_texture_type != FLAG_CARRIER_WEAPON {
+                    commands.entity(player_entity).insert(WeaponSwap::new(FLAG_CARRIER_WEAPON));
+                }
+                FlagEvent::Taken { by: player_network.id }
+            }
+            FlagState::Dropped(_) => {
+                return_flag(&mut state, &mut transform, home);
+                FlagEvent::Returned { by: Some(player_network.id), carrier: None }
+            }
+            _ => continue,
+        };
+        send_flag_changed(tick.0, flag.team, flag_event, &mut server);
+    }
+}
+
+/// Carried flags follow their carrier and fall when it dies or leaves; dropped ones go home when their timer ends.
+#[allow(clippy::type_complexity)]
+pub fn carry_flags(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    time: Res<Time>,
+    kill_volume: Res<KillVolume>,
+    mut flag_query: Query<(Entity, &Flag, &FlagHome, &mut FlagState, &mut Transform)>,
+    carrier_query: Query<(&Transform, Has<Dead>), (With<FlagCarrier>, Without<Flag>)>,
+) {
+    for (flag_entity, flag, home, mut state, mut transform) in flag_query.iter_mut() {
+        let flag_event = match &mut *state {
+            FlagState::Carried { player, id } => {
+                let (player, id) = (*player, *id);
+                match carrier_query.get(player) {
+                    Ok((carrier_transform, false)) => {
+                        transform.translation = carrier_transform.translation + FLAG_CARRY_OFFSET.extend(0.0);
+                        continue;
+                    }
+                    Ok((_, true)) => {
+                        commands.entity(player).remove::<FlagCarrier>();
+                    }
+                    Err(_) => {}
+                }
+
+                if kill_volume.is_safe(transform.translation.truncate()) {
+                    *state = FlagState::dropped();
+                    commands.entity(flag_entity).remove::<ColliderDisabled>();
+                    FlagEvent::Dropped { by: id, translation: transform.translation.into(), return_in_secs: FLAG_RETURN_SECS }
+                } else {
+                    return_flag(&mut state, &mut transform, home);
+                    commands.entity(flag_entity).remove::<ColliderDisabled>();
+                    FlagEvent::Returned { by: None, carrier: Some(id) }
+                }
+            }
+            FlagState::Dropped(timer) => {
+                if !timer.tick(time.delta()).finished() {
+                    continue;
+                }
+                return_flag(&mut state, &mut transform, home);
+                FlagEvent::Returned { by: None, carrier: None }
+            }
+            FlagState::AtBase => continue,
+        };
+        send_flag_changed(tick.0, flag.team, flag_event, &mut server);
+    }
+}
+
+/// Every flag back at its base for the start of a match.
+pub fn reset_flags(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    mut flag_query: Query<(Entity, &Flag, &FlagHome, &mut FlagState, &mut Transform)>,
+    carrier_query: Query<Entity, With<FlagCarrier>>,
+) {
+    for (flag_entity, flag, home, mut state, mut transform) in flag_query.iter_mut() {
+        return_flag(&mut state, &mut transform, home);
+        commands.entity(flag_entity).remove::<ColliderDisabled>();
+        send_flag_changed(tick.0, flag.team, FlagEvent::Reset, &mut server);
+    }
+    for carrier in carrier_query.iter() {
+        commands.entity(carrier).remove::<FlagCarrier>();
+    }
+}
+
+fn return_flag(state: &mut Mut<FlagState>, transform: &mut Mut<Transform>, home: &FlagHome) {
+    **state = FlagState::AtBase;
+    transform.translation = home.0.extend(0.0);
+}
+
+fn send_flag_changed(tick: u64, team: Team, event: FlagEvent, server: &mut RenetServer) {
+    let message = match bincode::serialize(&TickedServerMessage {
+        tick,
+        message: ServerMessages::FlagChanged { team, event },
+    }) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Erreur de sérialisation FlagChanged: {:?}", e);
+            return;
+        }
+    };
+
+    server.broadcast_message(ServerChannel::ServerMessages, message);
+}
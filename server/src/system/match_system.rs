@@ -0,0 +1,201 @@
+use crate::config::ServerSettings;
+use crate::game_mode::ActiveGameMode;
+use crate::plugin::{ServerLobby, ServerTick};
+use crate::system::flag_system::FlagCaptured;
+use crate::system::respawn_system::PlayerKilled;
+use bevy::log::error;
+use bevy::prelude::{Commands, Entity, EventReader, NextState, Query, Res, ResMut, Resource, State, Time, Timer, TimerMode, With};
+use bevy_rapier2d::dynamics::RigidBodyDisabled;
+use bevy_rapier2d::geometry::ColliderDisabled;
+use bevy_renet2::prelude::{RenetServer, ServerEvent};
+use game_core::game_mode::component::{leader, MatchPhase};
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::player::component::{Dead, LastAttacker, PlayerNetwork};
+
+/// Countdown of the current match phase; `dirty` asks for a new `MatchState` to be sent.
+#[derive(Debug, Resource)]
+pub struct MatchClock {
+    pub timer: Timer,
+    /// Warmup without enough players, the countdown does not run.
+    pub waiting_for_players: bool,
+    dirty: bool,
+}
+
+impl Default for MatchClock {
+    fn default() -> Self {
+        Self {
+            timer: Timer::default(),
+            waiting_for_players: true,
+            dirty: true,
+        }
+    }
+}
+
+impl MatchClock {
+    fn restart(&mut self, secs: f32) {
+        self.timer = Timer::from_seconds(secs, TimerMode::Once);
+        self.dirty = true;
+    }
+}
+
+pub fn track_match_players(
+    mut server_events: EventReader<ServerEvent>,
+    mut game_mode: ResMut<ActiveGameMode>,
+    mut clock: ResMut<MatchClock>,
+) {
+    for event in server_events.read() {
+        match event {
+            ServerEvent::ClientConnected { client_id } => game_mode.0.player_joined(*client_id),
+            ServerEvent::ClientDisconnected { client_id, .. } => game_mode.0.player_left(*client_id),
+        }
+        clock.dirty = true;
+    }
+}
+
+pub fn score_kills(
+    mut player_killed: EventReader<PlayerKilled>,
+    phase: Res<State<MatchPhase>>,
+    mut game_mode: ResMut<ActiveGameMode>,
+    mut clock: ResMut<MatchClock>,
+) {
+    for kill in player_killed.read() {
+        if *phase.get() == MatchPhase::InProgress {
+            game_mode.0.player_killed(kill);
+            clock.dirty = true;
+        }
+    }
+}
+
+pub fn score_captures(
+    mut flag_captured: EventReader<FlagCaptured>,
+    phase: Res<State<MatchPhase>>,
+    mut game_mode: ResMut<ActiveGameMode>,
+    mut clock: ResMut<MatchClock>,
+) {
+    for FlagCaptured { team } in flag_captured.read() {
+        if *phase.get() == MatchPhase::InProgress {
+            game_mode.0.flag_captured(team.other());
+            clock.dirty = true;
+        }
+    }
+}
+
+/// Moves the match to its next phase once the countdown, score limit or player count says so.
+pub fn advance_match(
+    time: Res<Time>,
+    settings: Res<ServerSettings>,
+    lobby: Res<ServerLobby>,
+    phase: Res<State<MatchPhase>>,
+    mut next_phase: ResMut<NextState<MatchPhase>>,
+    game_mode: Res<ActiveGameMode>,
+    mut clock: ResMut<MatchClock>,
+) {
+    let rules = &settings.match_settings;
+    let clock = &mut *clock;
+    match phase.get() {
+        MatchPhase::Warmup => {
+            let waiting_for_players = lobby.players.len() < rules.min_players.max(1);
+            if waiting_for_players != clock.waiting_for_players {
+                clock.waiting_for_players = waiting_for_players;
+                clock.restart(rules.warmup_secs);
+            }
+            if !waiting_for_players && clock.timer.tick(time.delta()).finished() {
+                next_phase.set(MatchPhase::InProgress);
+            }
+        }
+        MatchPhase::InProgress => {
+            let score_reached = rules.score_limit > 0
+                && game_mode.0.scores().iter().any(|entry| entry.score >= rules.score_limit as i32);
+            if lobby.players.is_empty() {
+                next_phase.set(MatchPhase::Warmup);
+            } else if clock.timer.tick(time.delta()).finished() || score_reached {
+                next_phase.set(MatchPhase::Ended);
+            }
+        }
+        MatchPhase::Ended => {
+            if clock.timer.tick(time.delta()).finished() {
+                next_phase.set(MatchPhase::Warmup);
+            }
+        }
+    }
+}
+
+pub fn enter_warmup(
+    settings: Res<ServerSettings>,
+    mut game_mode: ResMut<ActiveGameMode>,
+    mut clock: ResMut<MatchClock>,
+) {
+    game_mode.0.reset_scores();
+    clock.waiting_for_players = true;
+    clock.restart(settings.match_settings.warmup_secs);
+}
+
+/// Clears the scores and sends every player back to a spawn point with full health.
+/// No `PlayerDied` is sent, a restart is not a death: `player_respawn` picks the players up
+/// on its next run and broadcasts their `PlayerRespawned`, which clients apply to living players too.
+pub fn enter_match(
+    mut commands: Commands,
+    settings: Res<ServerSettings>,
+    mut lobby: ResMut<ServerLobby>,
+    mut game_mode: ResMut<ActiveGameMode>,
+    mut clock: ResMut<MatchClock>,
+    player_query: Query<Entity, With<PlayerNetwork>>,
+) {
+    game_mode.0.reset_scores();
+    lobby.reset_stats();
+    clock.restart(settings.match_settings.time_limit_secs);
+
+    for entity in player_query.iter() {
+        commands.entity(entity)
+            .insert((Dead::new(0.0), RigidBodyDisabled, ColliderDisabled))
+            .remove::<LastAttacker>();
+    }
+}
+
+pub fn enter_results(
+    settings: Res<ServerSettings>,
+    mut clock: ResMut<MatchClock>,
+) {
+    clock.restart(settings.match_settings.results_secs);
+}
+
+pub fn send_match_state(
+    mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    settings: Res<ServerSettings>,
+    phase: Res<State<MatchPhase>>,
+    game_mode: Res<ActiveGameMode>,
+    mut clock: ResMut<MatchClock>,
+) {
+    if !clock.dirty {
+        return;
+    }
+    clock.dirty = false;
+
+    let phase = *phase.get();
+    let scores = game_mode.0.scores();
+    let winner = match phase {
+        MatchPhase::Ended => leader(&scores).map(|entry| entry.owner),
+        _ => None,
+    };
+    let waiting_for_players = phase == MatchPhase::Warmup && clock.waiting_for_players;
+
+    let message = match bincode::serialize(&TickedServerMessage {
+        tick: tick.0,
+        message: ServerMessages::MatchState {
+            mode: game_mode.0.name().to_string(),
+            phase,
+            remaining_secs: (!waiting_for_players).then(|| clock.timer.remaining_secs()),
+            score_limit: settings.match_settings.score_limit,
+            scores,
+            winner,
+        },
+    }) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Erreur de sérialisation MatchState: {:?}", e);
+            return;
+        }
+    };
+    server.broadcast_message(ServerChannel::ServerMessages, message);
+}
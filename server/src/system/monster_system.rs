@@ -0,0 +1,180 @@
+use crate::plugin::ServerTick;
+use bevy::log::error;
+use bevy::math::Vec2;
+use bevy::prelude::{Commands, Entity, Has, Query, Res, ResMut, Time, Transform, With, Without};
+use bevy_rapier2d::dynamics::Velocity;
+use bevy_rapier2d::pipeline::QueryFilter;
+use bevy_rapier2d::plugin::{RapierContext, ReadRapierContext};
+use bevy_renet2::prelude::RenetServer;
+use game_core::decor::component::KillVolume;
+use game_core::monster::command::spawn_monster_entity;
+use game_core::monster::component::{monster_physics, Monster, MonsterAi, MonsterBehavior, MonsterSpawner, MonsterType};
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::player::component::{Dead, Health, Invulnerable, LastAttacker, PlayerNetwork};
+
+/// How far below its feet a monster looks for ground before walking on.
+const LEDGE_PROBE_DEPTH: f32 = 12.0;
+
+/// Spawns a monster at every spawner whose previous monster died long enough ago.
+pub fn spawn_monsters(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spawner_query: Query<(&Transform, &mut MonsterSpawner)>,
+    monster_query: Query<(), With<Monster>>,
+) {
+    for (transform, mut spawner) in spawner_query.iter_mut() {
+        if spawner.monster.is_some_and(|monster| monster_query.contains(monster)) {
+            continue;
+        }
+        if spawner.monster.take().is_some() {
+            spawner.respawn.reset();
+        }
+        if !spawner.respawn.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let monster = spawn_monster_entity(&mut commands, spawner.monster_type, transform.translation);
+        commands.entity(monster).insert(monster_physics(&spawner.monster_type));
+        spawner.monster = Some(monster);
+    }
+}
+
+/// Patrols between platform edges, chases the nearest player in sight and hits it in melee range.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn monster_ai(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    time: Res<Time>,
+    rapier_context: ReadRapierContext,
+    mut monster_query: Query<(Entity, &Transform, &MonsterType, &mut MonsterAi, &mut Velocity), With<Monster>>,
+    mut player_query: Query<(Entity, &PlayerNetwork, &Transform, &mut Health, Has<Invulnerable>), (Without<Dead>, Without<Monster>)>,
+) {
+    let Ok(context) = rapier_context.single() else { return };
+
+    for (entity, transform, monster_type, mut ai, mut velocity) in monster_query.iter_mut() {
+        let stats = monster_type.stats();
+        let position = transform.translation.truncate();
+        ai.attack_cooldown.tick(time.delta());
+        ai.stun.tick(time.delta());
+
+        let target = player_query.iter()
+            .map(|(entity, _, player_transform, ..)| (entity, player_transform.translation.truncate() - position))
+            .filter(|(_, offset)| offset.length() <= stats.sight_range && in_sight(&context, position, *offset))
+            .min_by(|(_, a), (_, b)| a.length_squared().total_cmp(&b.length_squared()));
+
+        let Some((target, offset)) = target else {
+            ai.behavior = MonsterBehavior::Patrol;
+            if !ground_ahead(&context, position, ai.direction, stats.half_size) || blocked_ahead(&context, entity, position, ai.direction, stats.half_size) {
+                ai.direction = -ai.direction;
+            }
+            steer(&mut velocity, &ai, ai.direction * stats.patrol_speed);
+            continue;
+        };
+
+        if offset.x.abs() > 1.0 {
+            ai.direction = offset.x.signum();
+        }
+
+        if offset.length() > stats.melee_range {
+            ai.behavior = MonsterBehavior::Chase(target);
+            let speed = if ground_ahead(&context, position, ai.direction, stats.half_size) {
+                ai.direction * stats.chase_speed
+            } else {
+                0.0
+            };
+            steer(&mut velocity, &ai, speed);
+            continue;
+        }
+
+        ai.behavior = MonsterBehavior::Attack(target);
+        steer(&mut velocity, &ai, 0.0);
+        if !ai.attack_cooldown.finished() {
+            continue;
+        }
+        ai.attack_cooldown.reset();
+
+        let Ok((_, player_network, _, mut health, invulnerable)) = player_query.get_mut(target) else { continue };
+        if invulnerable {
+            continue;
+        }
+        let taken = health.take_damage(stats.melee_damage);
+        // The monster lands the last hit: dying now is nobody's kill, earlier assailants only assist.
+        commands.entity(target).remove::<LastAttacker>();
+
+        let message = match bincode::serialize(&TickedServerMessage {
+            tick: tick.0,
+            message: ServerMessages::PlayerHitByMonster {
+                victim: player_network.id,
+                damage: taken,
+                health: health.current,
+            },
+        }) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Erreur de sérialisation PlayerHitByMonster: {:?}", e);
+                continue;
+            }
+        };
+        server.broadcast_message(ServerChannel::ServerMessages, message);
+    }
+}
+
+/// Level geometry only: other bodies and sensors neither block sight nor count as ground.
+fn level_filter() -> QueryFilter<'static> {
+    QueryFilter::only_fixed().exclude_sensors()
+}
+
+fn in_sight(context: &RapierContext, position: Vec2, offset: Vec2) -> bool {
+    context.cast_ray(position, offset, 1.0, true, level_filter()).is_none()
+}
+
+/// Sets the horizontal speed, unless a knockback is still carrying the monster.
+fn steer(velocity: &mut Velocity, ai: &MonsterAi, speed: f32) {
+    if !ai.stunned() {
+        velocity.linvel.x = speed;
+    }
+}
+
+fn ground_ahead(context: &RapierContext, position: Vec2, direction: f32, half_size: Vec2) -> bool {
+    let probe = position + Vec2::new(direction * (half_size.x + 4.0), 0.0);
+    context.cast_ray(probe, Vec2::NEG_Y, half_size.y + LEDGE_PROBE_DEPTH, true, level_filter()).is_some()
+}
+
+/// Walls and other bodies, so patrolling monsters turn back instead of pushing each other.
+fn blocked_ahead(context: &RapierContext, monster: Entity, position: Vec2, direction: f32, half_size: Vec2) -> bool {
+    let filter = QueryFilter::default().exclude_sensors().exclude_rigid_body(monster);
+    context.cast_ray(position, Vec2::new(direction, 0.0), half_size.x + 4.0, true, filter).is_some()
+}
+
+/// Removes monsters with no health left or outside the level.
+pub fn monster_death(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    kill_volume: Res<KillVolume>,
+    monster_query: Query<(Entity, &Transform, &Health, Option<&LastAttacker>), With<Monster>>,
+) {
+    for (entity, transform, health, last_attacker) in monster_query.iter() {
+        if !health.is_dead() && kill_volume.is_safe(transform.translation.truncate()) {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+
+        let message = match bincode::serialize(&TickedServerMessage {
+            tick: tick.0,
+            message: ServerMessages::MonsterKilled {
+                entity: entity.to_bits(),
+                killer: last_attacker.map(|attacker| attacker.id),
+            },
+        }) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Erreur de sérialisation MonsterKilled: {:?}", e);
+                continue;
+            }
+        };
+        server.broadcast_message(ServerChannel::ServerMessages, message);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::plugin::ServerTick;
+use bevy::log::error;
+use bevy::prelude::{Commands, Entity, EventReader, Query, Res, ResMut, Time, Without};
+use bevy_rapier2d::geometry::ColliderDisabled;
+use bevy_rapier2d::pipeline::CollisionEvent;
+use bevy_renet2::prelude::RenetServer;
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::pickup::component::{Pickup, PickupCooldown, PickupRespawn};
+use game_core::player::component::{Dead, Health, PlayerNetwork, WeaponInventory};
+use game_core::weapon::definition::WeaponDefinitions;
+use std::collections::HashSet;
+
+/// Gives a touched pickup to the player; the first contact of the frame wins.
+#[allow(clippy::type_complexity)]
+pub fn collect_pickups(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    pickup_query: Query<(&Pickup, &PickupRespawn), Without<PickupCooldown>>,
+    mut player_query: Query<(&PlayerNetwork, &mut Health, &mut WeaponInventory), Without<Dead>>,
+    weapon_definitions: Res<WeaponDefinitions>,
+) {
+    let mut collected: HashSet<Entity> = HashSet::new();
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+
+        let (pickup_entity, player_entity) = match (pickup_query.contains(*e1), pickup_query.contains(*e2)) {
+            (true, false) => (*e1, *e2),
+            (false, true) => (*e2, *e1),
+            _ => continue,
+        };
+        if collected.contains(&pickup_entity) {
+            continue;
+        }
+        let Ok((pickup, respawn)) = pickup_query.get(pickup_entity) else { continue };
+        let Ok((player_network, mut health, mut inventory)) = player_query.get_mut(player_entity) else { continue };
+
+        match pickup {
+            Pickup::Weapon(weapon) => {
+                if !inventory.add(weapon.clone(), &weapon_definitions) && !inventory.restock(Some(weapon), &weapon_definitions) {
+                    continue;
+                }
+            }
+            Pickup::Ammo => {
+                if !inventory.restock(None, &weapon_definitions) {
+                    continue;
+                }
+            }
+            Pickup::Health(amount) => {
+                if health.heal(*amount) == 0 {
+                    continue;
+                }
+            }
+        }
+
+        collected.insert(pickup_entity);
+        commands.entity(pickup_entity).insert((PickupCooldown::new(respawn), ColliderDisabled));
+
+        let message = match bincode::serialize(&TickedServerMessage {
+            tick: tick.0,
+            message: ServerMessages::PickupCollected { id: player_network.id, pickup: pickup.clone() },
+        }) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Erreur de sérialisation PickupCollected: {:?}", e);
+                continue;
+            }
+        };
+        server.broadcast_message(ServerChannel::ServerMessages, message);
+    }
+}
+
+pub fn respawn_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pickup_query: Query<(Entity, &mut PickupCooldown)>,
+) {
+    for (entity, mut cooldown) in pickup_query.iter_mut() {
+        if cooldown.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<(PickupCooldown, ColliderDisabled)>();
+        }
+    }
+}
@@ -1,71 +1,282 @@
-use bevy::asset::Assets;
-use bevy::image::TextureAtlasLayout;
+use crate::plugin::ServerTick;
 use bevy::math::Vec3;
-use bevy::prelude::{Commands, EventReader, GlobalTransform, Query, Res, ResMut, With};
+use bevy::prelude::{Commands, Component, Entity, EventReader, GlobalTransform, Has, Query, Res, Time, Transform, With, Without};
 use bevy_rapier2d::dynamics::Velocity;
 use bevy_rapier2d::pipeline::CollisionEvent;
-use game_core::player::component::{Grounded, JumpCounter, Player, PlayerChildren, PlayerInput, PlayerWeaponSelected};
-use game_core::player::math::{apply_jump_velocity, apply_velocity};
+use bevy_renet2::prelude::ClientId;
+use game_core::decor::component::{Ground, OneWayPlatform};
+use game_core::game_mode::flag::{slow_flag_carrier, FlagCarrier, FLAG_CARRIER_WEAPON};
+use game_core::player::component::{Dead, Grounded, JumpCounter, Player, PlayerChildren, PlayerInput, PlayerNetwork, PlayerWeaponSelected, WeaponInventory};
+use game_core::player::math::step_player_movement;
 use game_core::weapon::command::spawn_weapon_fx;
-use game_core::weapon::component::Weapon;
-use game_core::weapon::fx_texture::{WeaponFxTextureType, WeaponFxTextures};
+use game_core::weapon::component::{spawn_grenade_physics_bundle, Grenade, Pellet, PelletVolley, ProjectileDamage, ProjectileOwner, ProjectileRange, Weapon};
+use game_core::weapon::definition::{WeaponDefinition, WeaponDefinitions};
+use game_core::weapon::fx_texture::WeaponFxTextureType;
+use game_core::weapon::math::{projectile_directions, spread_seed};
+use game_core::weapon::state::WeaponSwap;
+use game_core::weapon::texture::WeaponTextureType;
+use std::collections::HashMap;
 
+/// Sequence of the last input `player_move` applied, echoed to its client for reconciliation.
+#[derive(Component, Debug, Default)]
+pub struct AppliedInputSequence(pub u32);
+
+#[allow(clippy::type_complexity)]
 pub fn player_move(
     mut query: Query<(
         &Player,
-        &PlayerInput,
+        &mut PlayerInput,
+        &mut AppliedInputSequence,
         &mut Velocity,
         &Grounded,
-        &mut JumpCounter
+        &mut JumpCounter,
+        Has<FlagCarrier>,
+        Has<Dead>,
     )>
 ) {
-    for (player, input, mut velocity, grounded, mut jump_counter) in query.iter_mut() {
-        apply_velocity(player, input, &mut velocity);
-        apply_jump_velocity(player, input, &mut velocity, &mut jump_counter, grounded);
+    for (player, mut input, mut applied_input, mut velocity, grounded, mut jump_counter, flag_carrier, dead) in query.iter_mut() {
+        applied_input.0 = input.sequence;
+        if !dead {
+            step_player_movement(player, &input, &mut velocity, &mut jump_counter, grounded);
+            if flag_carrier {
+                slow_flag_carrier(&mut velocity);
+            }
+        }
+        // A jump is applied once, even when no newer input arrives before the next frame.
+        if input.jump {
+            input.jump = false;
+        }
     }
 }
 
+/// Distance from the player origin down to the bottom of its collider, before scaling.
+const PLAYER_FEET_OFFSET: f32 = 80.0;
+/// How far the feet may have sunk into a one-way platform when the ground sensor touches it.
+const ONE_WAY_LANDING_TOLERANCE: f32 = 4.0;
+
+/// Ground sensors touching a platform give the jumps back; one-way platforms only count when landed on from above.
+#[allow(clippy::type_complexity)]
 pub fn player_jump_control(
     mut collision_events: EventReader<CollisionEvent>,
-    mut player_query: Query<(&mut Grounded, &mut JumpCounter, &PlayerChildren), With<Player>>,
+    mut player_query: Query<(Entity, &mut Grounded, &mut JumpCounter, &PlayerChildren, &Transform, &Velocity), With<Player>>,
+    one_way_query: Query<&Ground, With<OneWayPlatform>>,
 ) {
-    for (mut grounded, mut jump_counter, player_children) in player_query.iter_mut() {
-        if let Some(sensor) = player_children.sensor {
-            for event in collision_events.read() {
-                if let CollisionEvent::Started(e1, e2, _) = event {
-                    if e1 == &sensor || e2 == &sensor {
-                        grounded.0 = true;
-                        jump_counter.reset();
-                    }
-                }
-            }
+    let sensors: HashMap<Entity, Entity> = player_query.iter()
+        .filter_map(|(entity, _, _, children, ..)| children.sensor.map(|sensor| (sensor, entity)))
+        .collect();
+
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+        let (player, other) = match (sensors.get(e1), sensors.get(e2)) {
+            (Some(player), _) => (*player, *e2),
+            (_, Some(player)) => (*player, *e1),
+            _ => continue,
+        };
+        let Ok((_, mut grounded, mut jump_counter, _, transform, velocity)) = player_query.get_mut(player) else { continue };
+        if let Ok(platform) = one_way_query.get(other)
+            && !lands_on(platform, transform, velocity)
+        {
+            continue;
         }
+
+        grounded.0 = true;
+        jump_counter.reset();
     }
 }
 
+fn lands_on(platform: &Ground, transform: &Transform, velocity: &Velocity) -> bool {
+    let feet = transform.translation.y - PLAYER_FEET_OFFSET * transform.scale.y;
+    let platform_top = platform.position.y + platform.size.y / 2.0;
+    velocity.linvel.y <= 0.0 && feet >= platform_top - ONE_WAY_LANDING_TOLERANCE
+}
+
 
+#[allow(clippy::type_complexity)]
 pub fn player_shoot(
     mut commands: Commands,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    mut weapon_fx_textures: Res<WeaponFxTextures>,
-    player_query: Query<(&PlayerInput, &PlayerWeaponSelected, &PlayerChildren), With<Player>>,
+    time: Res<Time>,
+    tick: Res<ServerTick>,
+    weapon_definitions: Res<WeaponDefinitions>,
+    mut player_query: Query<(
+        &mut PlayerInput,
+        &PlayerWeaponSelected,
+        &PlayerChildren,
+        &PlayerNetwork,
+        &mut WeaponInventory,
+        Has<FlagCarrier>,
+    ), (With<Player>, Without<Dead>, Without<WeaponSwap>)>,
     weapon_query: Query<&GlobalTransform, With<Weapon>>,
 ) {
-    for (player_input, player_weapon_selected, children) in player_query.iter() {
-        let Ok(global_transform) = weapon_query.get(children.weapon) else { return };
-
-        if player_input.shoot {
-            let position = global_transform.transform_point(Vec3::new(52.5, 0.0, 0.0));
-
-            spawn_weapon_fx(
-                &mut commands,
-                &mut texture_atlas_layouts,
-                &mut weapon_fx_textures,
-                position,
-                &WeaponFxTextureType::from(&player_weapon_selected.weapon_texture_type),
-                player_input.aim_direction,
-                true,
-            );
+    for (mut player_input, player_weapon_selected, children, player_network, mut inventory, flag_carrier) in player_query.iter_mut() {
+        if flag_carrier && player_weapon_selected.weapon_texture_type != FLAG_CARRIER_WEAPON {
+            continue;
+        }
+        let Some(definition) = weapon_definitions.get(&player_weapon_selected.weapon_texture_type) else { continue };
+        let Some(weapon_state) = inventory.state_mut(&player_weapon_selected.weapon_texture_type) else { continue };
+
+        weapon_state.tick(time.delta_secs(), definition);
+        if std::mem::take(&mut player_input.reload) {
+            weapon_state.start_reload(definition);
+        }
+        if !weapon_state.try_fire(player_input.shoot, definition) {
+            continue;
+        }
+
+        let Ok(global_transform) = weapon_query.get(children.weapon) else { continue };
+        let origin = global_transform.transform_point(Vec3::new(52.5, 0.0, 0.0));
+        fire_weapon(
+            &mut commands,
+            tick.0,
+            origin,
+            player_input.aim_direction,
+            player_network.id,
+            &player_weapon_selected.weapon_texture_type,
+            definition,
+        );
+    }
+}
+
+/// Draws the weapon once its swap delay is over, with the ammunition it was holstered with.
+pub fn finish_weapon_swaps(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut WeaponSwap, &mut PlayerWeaponSelected)>,
+) {
+    for (entity, mut swap, mut player_weapon_selected) in query.iter_mut() {
+        if swap.timer.tick(time.delta()).finished() {
+            player_weapon_selected.weapon_texture_type = swap.weapon.clone();
+            commands.entity(entity).remove::<WeaponSwap>();
         }
     }
-}
\ No newline at end of file
+}
+
+/// Spawns the projectiles of one shot; several pellets are grouped under a `PelletVolley` for replication.
+fn fire_weapon(
+    commands: &mut Commands,
+    tick: u64,
+    origin: Vec3,
+    aim_direction: f32,
+    owner: ClientId,
+    weapon_texture_type: &WeaponTextureType,
+    definition: &WeaponDefinition,
+) {
+    let weapon_fx_texture_type = WeaponFxTextureType::from(weapon_texture_type);
+    let seed = spread_seed(tick, owner);
+    let directions = projectile_directions(aim_direction, definition.spread_degrees, definition.projectile_count, seed);
+
+    let volley = (directions.len() > 1).then(|| commands.spawn((
+        PelletVolley {
+            origin,
+            aim_direction,
+            spread_degrees: definition.spread_degrees,
+            seed,
+            pellet_count: directions.len() as u8,
+            speed: definition.projectile_speed,
+            fired_tick: tick,
+        },
+        ProjectileOwner(owner),
+        weapon_fx_texture_type.clone(),
+    )).id());
+
+    for (index, direction) in directions.into_iter().enumerate() {
+        let projectile = spawn_weapon_fx(
+            commands,
+            origin,
+            &weapon_fx_texture_type,
+            direction,
+            definition.grenade.is_none().then_some(definition.projectile_speed),
+        );
+
+        let mut projectile_commands = commands.entity(projectile);
+        projectile_commands.insert((
+            ProjectileOwner(owner),
+            ProjectileDamage(definition.damage),
+        ));
+        if let Some(falloff) = definition.falloff {
+            projectile_commands.insert(falloff);
+        }
+        if let Some(volley) = volley {
+            projectile_commands.insert(Pellet { volley, index: index as u8 });
+        }
+        // Grenades bounce around until their fuse ends instead of vanishing out of range.
+        match &definition.grenade {
+            Some(grenade) => {
+                projectile_commands.insert((
+                    spawn_grenade_physics_bundle(direction, definition.projectile_speed, grenade),
+                    Grenade::new(grenade),
+                ));
+            }
+            None => {
+                projectile_commands.insert(ProjectileRange { origin, max_distance: definition.range });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::{App, Update};
+    use bevy::prelude::Events;
+    use bevy::math::Vec2;
+    use bevy_rapier2d::rapier::geometry::CollisionEventFlags;
+
+    fn spawn_player(app: &mut App, feet: f32, vertical_speed: f32) -> (Entity, Entity) {
+        let sensor = app.world_mut().spawn_empty().id();
+        let transform = Transform::from_xyz(0.0, feet + PLAYER_FEET_OFFSET * 0.5, 0.0).with_scale(Vec3::splat(0.5));
+        let player = app.world_mut().spawn((
+            Player { speed: 350.0 },
+            Grounded(false),
+            JumpCounter { jumps_left: 0, max_jumps: 2 },
+            PlayerChildren { pivot: Entity::PLACEHOLDER, weapon: Entity::PLACEHOLDER, sensor: Some(sensor) },
+            transform,
+            Velocity::linear(Vec2::new(0.0, vertical_speed)),
+        )).id();
+        (player, sensor)
+    }
+
+    fn touch(app: &mut App, sensor: Entity, platform: Entity) {
+        app.world_mut().resource_mut::<Events<CollisionEvent>>()
+            .send(CollisionEvent::Started(platform, sensor, CollisionEventFlags::SENSOR));
+    }
+
+    fn jumps_left(app: &App, player: Entity) -> u8 {
+        app.world().get::<JumpCounter>(player).unwrap().jumps_left
+    }
+
+    #[test]
+    fn one_way_platforms_only_give_jumps_back_when_landed_on() {
+        let mut app = App::new();
+        app.add_event::<CollisionEvent>();
+        app.add_systems(Update, player_jump_control);
+        let platform_size = Vec2::new(120.0, 10.0);
+        let platform = app.world_mut().spawn((Ground::new(platform_size, Vec2::ZERO), OneWayPlatform)).id();
+        let (jumping, jumping_sensor) = spawn_player(&mut app, -10.0, 300.0);
+        let (landing, landing_sensor) = spawn_player(&mut app, 4.0, -200.0);
+
+        touch(&mut app, jumping_sensor, platform);
+        touch(&mut app, landing_sensor, platform);
+        app.update();
+
+        assert_eq!(jumps_left(&app, jumping), 0);
+        assert!(!app.world().get::<Grounded>(jumping).unwrap().0);
+        assert_eq!(jumps_left(&app, landing), 2);
+        assert!(app.world().get::<Grounded>(landing).unwrap().0);
+    }
+
+    #[test]
+    fn solid_ground_gives_jumps_back_to_every_touching_player() {
+        let mut app = App::new();
+        app.add_event::<CollisionEvent>();
+        app.add_systems(Update, player_jump_control);
+        let ground = app.world_mut().spawn(Ground::new(Vec2::new(800.0, 25.0), Vec2::ZERO)).id();
+        let (first, first_sensor) = spawn_player(&mut app, 12.5, 0.0);
+        let (second, second_sensor) = spawn_player(&mut app, 12.5, 0.0);
+
+        touch(&mut app, first_sensor, ground);
+        touch(&mut app, second_sensor, ground);
+        app.update();
+
+        assert_eq!((jumps_left(&app, first), jumps_left(&app, second)), (2, 2));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::config::ServerSettings;
+use crate::plugin::{ServerLobby, ServerTick};
+use crate::system::team_system::team_of;
+use bevy::log::error;
+use bevy::math::Vec2;
+use bevy::prelude::{Commands, Entity, EventReader, Has, Query, Res, ResMut, Time, Transform, With, Without};
+use bevy_rapier2d::dynamics::Velocity;
+use bevy_rapier2d::pipeline::CollisionEvent;
+use bevy_renet2::prelude::{ClientId, RenetServer};
+use game_core::game_mode::component::Team;
+use game_core::monster::component::{Monster, MonsterAi};
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::player::component::{Dead, Health, Invulnerable, LastAttacker, PlayerNetwork};
+use game_core::weapon::component::{Grenade, Pellet, PelletVolley, ProjectileDamage, ProjectileOwner, ProjectileRange};
+use game_core::weapon::definition::DamageFalloff;
+use game_core::weapon::fx_texture::{FxComponent, WeaponFxTextureType};
+use std::collections::HashSet;
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn projectile_hit(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut server: ResMut<RenetServer>,
+    mut lobby: ResMut<ServerLobby>,
+    tick: Res<ServerTick>,
+    settings: Res<ServerSettings>,
+    projectile_query: Query<(&ProjectileOwner, &ProjectileDamage, &WeaponFxTextureType, &Transform, Option<&ProjectileRange>, Option<&DamageFalloff>), (With<FxComponent>, Without<Grenade>)>,
+    mut player_query: Query<(Entity, &PlayerNetwork, &mut Health, Has<Invulnerable>, Option<&Team>), Without<Dead>>,
+    mut monster_query: Query<&mut Health, (With<Monster>, Without<PlayerNetwork>)>,
+    team_query: Query<(&PlayerNetwork, &Team)>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+
+        let (projectile, other) = match (projectile_query.contains(*e1), projectile_query.contains(*e2)) {
+            (true, false) => (*e1, *e2),
+            (false, true) => (*e2, *e1),
+            _ => continue,
+        };
+        let Ok((owner, damage, weapon_fx_texture_type, transform, range, falloff)) = projectile_query.get(projectile) else { continue };
+        let last_attacker = LastAttacker { id: owner.0, weapon: weapon_fx_texture_type.into() };
+        let damage = match (range, falloff) {
+            (Some(range), Some(falloff)) => falloff.damage_at(damage.0, range.travelled(transform.translation), range.max_distance),
+            _ => damage.0,
+        };
+
+        if let Ok((player_entity, player_network, mut health, invulnerable, team)) = player_query.get_mut(other) {
+            if player_network.id == owner.0 {
+                continue;
+            }
+
+            if !invulnerable && settings.player_damage(team_of(&team_query, owner.0), team.copied()) {
+                let taken = health.take_damage(damage);
+                lobby.record_damage(owner.0, Some(player_network.id), taken);
+                commands.entity(player_entity).insert(last_attacker);
+                broadcast_player_hit(tick.0, owner.0, player_network.id, taken, health.current, &mut server);
+            }
+        } else if let Ok(mut health) = monster_query.get_mut(other) {
+            let taken = health.take_damage(damage);
+            lobby.record_damage(owner.0, None, taken);
+            commands.entity(other).insert(last_attacker);
+        }
+
+        if let Ok(mut projectile_commands) = commands.get_entity(projectile) {
+            projectile_commands.try_despawn();
+        }
+    }
+}
+
+/// Explodes grenades whose fuse ran out or that touched a monster or a player other than their owner.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn grenade_detonation(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut server: ResMut<RenetServer>,
+    mut lobby: ResMut<ServerLobby>,
+    tick: Res<ServerTick>,
+    time: Res<Time>,
+    settings: Res<ServerSettings>,
+    mut grenade_query: Query<(Entity, &mut Grenade, &Transform, &ProjectileOwner, &ProjectileDamage, &WeaponFxTextureType)>,
+    mut player_query: Query<(Entity, &PlayerNetwork, &Transform, &mut Health, &mut Velocity, Has<Invulnerable>, Option<&Team>), Without<Dead>>,
+    mut monster_query: Query<(Entity, &Transform, &mut Health, &mut Velocity, &mut MonsterAi), (With<Monster>, Without<PlayerNetwork>)>,
+    team_query: Query<(&PlayerNetwork, &Team)>,
+) {
+    let mut detonated: HashSet<Entity> = HashSet::new();
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else { continue };
+
+        for (grenade, other) in [(*e1, *e2), (*e2, *e1)] {
+            if let Ok((_, _, _, owner, ..)) = grenade_query.get(grenade)
+                && (monster_query.contains(other)
+                    || player_query.get(other).is_ok_and(|(_, player_network, ..)| player_network.id != owner.0))
+            {
+                detonated.insert(grenade);
+            }
+        }
+    }
+
+    for (entity, mut grenade, ..) in grenade_query.iter_mut() {
+        if grenade.fuse.tick(time.delta()).finished() {
+            detonated.insert(entity);
+        }
+    }
+
+    for grenade_entity in detonated {
+        let Ok((_, grenade, transform, owner, damage, weapon_fx_texture_type)) = grenade_query.get(grenade_entity) else { continue };
+        let last_attacker = LastAttacker { id: owner.0, weapon: weapon_fx_texture_type.into() };
+        let center = transform.translation.truncate();
+
+        let owner_team = team_of(&team_query, owner.0);
+        for (player_entity, player_network, player_transform, mut health, mut velocity, invulnerable, team) in player_query.iter_mut() {
+            let offset = player_transform.translation.truncate() - center;
+            let proximity = 1.0 - offset.length() / grenade.explosion_radius;
+            if proximity <= 0.0 {
+                continue;
+            }
+
+            velocity.linvel += offset.normalize_or(Vec2::Y) * grenade.knockback_speed * proximity;
+
+            if invulnerable || player_network.id == owner.0 || !settings.player_damage(owner_team, team.copied()) {
+                continue;
+            }
+            let taken = health.take_damage((damage.0 as f32 * proximity).round() as u32);
+            lobby.record_damage(owner.0, Some(player_network.id), taken);
+            commands.entity(player_entity).insert(last_attacker.clone());
+            broadcast_player_hit(tick.0, owner.0, player_network.id, taken, health.current, &mut server);
+        }
+
+        for (monster_entity, monster_transform, mut health, mut velocity, mut ai) in monster_query.iter_mut() {
+            let offset = monster_transform.translation.truncate() - center;
+            let proximity = 1.0 - offset.length() / grenade.explosion_radius;
+            if proximity <= 0.0 {
+                continue;
+            }
+
+            velocity.linvel += offset.normalize_or(Vec2::Y) * grenade.knockback_speed * proximity;
+            ai.knock_back();
+            let taken = health.take_damage((damage.0 as f32 * proximity).round() as u32);
+            lobby.record_damage(owner.0, None, taken);
+            commands.entity(monster_entity).insert(last_attacker.clone());
+        }
+
+        // Despawned first: the damage is dealt even if the explosion cannot be announced.
+        commands.entity(grenade_entity).despawn();
+
+        let message = match bincode::serialize(&TickedServerMessage {
+            tick: tick.0,
+            message: ServerMessages::Explosion {
+                owner: owner.0,
+                translation: transform.translation.into(),
+                radius: grenade.explosion_radius,
+            },
+        }) {
+            Ok(msg) => msg,
+            Err(e) => {
+                error!("Erreur de sérialisation Explosion: {:?}", e);
+                continue;
+            }
+        };
+        server.broadcast_message(ServerChannel::ServerMessages, message);
+    }
+}
+
+/// Removes volleys whose pellets all hit something or ran out of range.
+pub fn despawn_spent_volleys(
+    mut commands: Commands,
+    volley_query: Query<Entity, With<PelletVolley>>,
+    pellet_query: Query<&Pellet>,
+) {
+    for volley in volley_query.iter() {
+        if !pellet_query.iter().any(|pellet| pellet.volley == volley) {
+            commands.entity(volley).despawn();
+        }
+    }
+}
+
+fn broadcast_player_hit(
+    tick: u64,
+    attacker: ClientId,
+    victim: ClientId,
+    damage: u32,
+    health: u32,
+    server: &mut RenetServer,
+) {
+    let message = match bincode::serialize(&TickedServerMessage {
+        tick,
+        message: ServerMessages::PlayerHit { attacker, victim, damage, health },
+    }) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Erreur de sérialisation PlayerHit: {:?}", e);
+            return;
+        }
+    };
+
+    server.broadcast_message(ServerChannel::ServerMessages, message);
+}
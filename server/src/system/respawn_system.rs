@@ -0,0 +1,200 @@
+use crate::config::{ServerSettings, SpawnStrategy};
+use crate::plugin::{ServerLobby, ServerTick, SpawnRotation};
+use crate::system::team_system::team_of;
+use crate::system::wave_system::WaveState;
+use bevy::log::error;
+use bevy::math::{Vec2, Vec3};
+use bevy::prelude::{Commands, Entity, Event, EventWriter, Query, Res, ResMut, Time, Transform, With, Without};
+use bevy_rapier2d::dynamics::{RigidBodyDisabled, Velocity};
+use bevy_rapier2d::geometry::ColliderDisabled;
+use bevy_renet2::prelude::{ClientId, RenetServer};
+use game_core::decor::component::{KillVolume, SpawnPoint};
+use game_core::game_mode::component::Team;
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::player::component::{Dead, Health, Invulnerable, LastAttacker, PlayerNetwork, WeaponInventory, RESPAWN_DELAY_SECS, SPAWN_INVULNERABILITY_SECS};
+use game_core::weapon::definition::WeaponDefinitions;
+
+/// Picks where a player spawns; `FarthestFromEnemies` falls back to the rotation while no enemy is alive.
+pub fn select_spawn_point(
+    strategy: SpawnStrategy,
+    spawn_points: &[Vec3],
+    enemies: &[Vec3],
+    rotation: &mut SpawnRotation,
+) -> Vec3 {
+    if spawn_points.is_empty() {
+        return Vec3::ZERO;
+    }
+
+    if strategy == SpawnStrategy::FarthestFromEnemies && !enemies.is_empty() {
+        return spawn_points.iter()
+            .copied()
+            .max_by(|a, b| nearest_enemy_distance(*a, enemies).total_cmp(&nearest_enemy_distance(*b, enemies)))
+            .unwrap_or_default();
+    }
+
+    let spawn_point = spawn_points[rotation.0 % spawn_points.len()];
+    rotation.0 = rotation.0.wrapping_add(1);
+    spawn_point
+}
+
+fn nearest_enemy_distance(spawn_point: Vec3, enemies: &[Vec3]) -> f32 {
+    enemies.iter()
+        .map(|enemy| enemy.truncate().distance_squared(spawn_point.truncate()))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// A player died; `killer` is whoever hit them last, if anyone did since they spawned.
+/// Teams are captured at death so a later team change does not rewrite the score.
+#[derive(Debug, Event)]
+pub struct PlayerKilled {
+    pub victim: ClientId,
+    pub killer: Option<ClientId>,
+    pub victim_team: Option<Team>,
+    pub killer_team: Option<Team>,
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn player_death(
+    mut commands: Commands,
+    mut player_killed: EventWriter<PlayerKilled>,
+    mut server: ResMut<RenetServer>,
+    mut lobby: ResMut<ServerLobby>,
+    tick: Res<ServerTick>,
+    kill_volume: Res<KillVolume>,
+    mut player_query: Query<(Entity, &PlayerNetwork, &Transform, &Health, &mut Velocity, Option<&LastAttacker>, Option<&Team>), Without<Dead>>,
+    team_query: Query<(&PlayerNetwork, &Team)>,
+) {
+    for (entity, player_network, transform, health, mut velocity, last_attacker, victim_team) in player_query.iter_mut() {
+        if !health.is_dead() && kill_volume.is_safe(transform.translation.truncate()) {
+            continue;
+        }
+
+        velocity.linvel = Vec2::ZERO;
+        commands.entity(entity)
+            .insert((Dead::new(RESPAWN_DELAY_SECS), RigidBodyDisabled, ColliderDisabled))
+            .remove::<(LastAttacker, Invulnerable)>();
+
+        let killer = last_attacker.map(|attacker| attacker.id);
+        let killed = PlayerKilled {
+            victim: player_network.id,
+            killer,
+            victim_team: victim_team.copied(),
+            killer_team: killer.and_then(|killer| team_of(&team_query, killer)),
+        };
+        let team_kill = killed.victim_team.is_some() && killed.victim_team == killed.killer_team;
+        let assists = lobby.record_death(player_network.id, killer, team_kill);
+        player_killed.write(killed);
+        broadcast_message(tick.0, ServerMessages::PlayerDied {
+            victim: player_network.id,
+            killer,
+            weapon: last_attacker.map(|attacker| attacker.weapon.clone()),
+            assists,
+            respawn_in_secs: RESPAWN_DELAY_SECS,
+        }, &mut server);
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn player_respawn(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    mut lobby: ResMut<ServerLobby>,
+    tick: Res<ServerTick>,
+    time: Res<Time>,
+    settings: Res<ServerSettings>,
+    mut spawn_rotation: ResMut<SpawnRotation>,
+    spawn_point_query: Query<&Transform, (With<SpawnPoint>, Without<PlayerNetwork>)>,
+    mut player_query: Query<(Entity, &PlayerNetwork, &mut WeaponInventory, &mut Transform, &mut Velocity, &mut Health, Option<&mut Dead>, Option<&Team>)>,
+    wave_state: Option<Res<WaveState>>,
+    weapon_definitions: Res<WeaponDefinitions>,
+) {
+    if wave_state.is_some_and(|wave_state| !wave_state.allows_respawn()) {
+        return;
+    }
+
+    let spawn_points: Vec<Vec3> = spawn_point_query.iter().map(|transform| transform.translation).collect();
+    let mut alive_players: Vec<(Vec3, Option<Team>)> = player_query.iter()
+        .filter(|(.., dead, _)| dead.is_none())
+        .map(|(_, _, _, transform, .., team)| (transform.translation, team.copied()))
+        .collect();
+
+    for (entity, player_network, mut inventory, mut transform, mut velocity, mut health, dead, team) in player_query.iter_mut() {
+        let Some(mut dead) = dead else { continue };
+        if !dead.respawn_timer.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let team = team.copied();
+        let enemies: Vec<Vec3> = alive_players.iter()
+            .filter(|(_, player_team)| team.is_none() || *player_team != team)
+            .map(|(position, _)| *position)
+            .collect();
+        let position = select_spawn_point(settings.spawn_strategy, &spawn_points, &enemies, &mut spawn_rotation);
+        alive_players.push((position, team));
+
+        transform.translation = position;
+        velocity.linvel = Vec2::ZERO;
+        health.current = health.max;
+        lobby.assailants.remove(&player_network.id);
+        inventory.refill(&weapon_definitions);
+        commands.entity(entity)
+            .remove::<(Dead, RigidBodyDisabled, ColliderDisabled)>()
+            .insert(Invulnerable::new(SPAWN_INVULNERABILITY_SECS));
+
+        broadcast_message(tick.0, ServerMessages::PlayerRespawned {
+            id: player_network.id,
+            translation: position.into(),
+            invulnerable_for_secs: SPAWN_INVULNERABILITY_SECS,
+        }, &mut server);
+    }
+}
+
+fn broadcast_message(tick: u64, message: ServerMessages, server: &mut RenetServer) {
+    let message = match bincode::serialize(&TickedServerMessage { tick, message }) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Erreur de sérialisation du message serveur: {:?}", e);
+            return;
+        }
+    };
+
+    server.broadcast_message(ServerChannel::ServerMessages, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPAWN_POINTS: [Vec3; 3] = [Vec3::new(-300.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(300.0, 0.0, 0.0)];
+
+    #[test]
+    fn no_spawn_point_spawns_at_origin() {
+        let mut rotation = SpawnRotation::default();
+        assert_eq!(select_spawn_point(SpawnStrategy::RoundRobin, &[], &[], &mut rotation), Vec3::ZERO);
+        assert_eq!(select_spawn_point(SpawnStrategy::FarthestFromEnemies, &[], &[Vec3::ONE], &mut rotation), Vec3::ZERO);
+    }
+
+    #[test]
+    fn farthest_spawn_point_from_the_nearest_enemy_is_picked() {
+        let mut rotation = SpawnRotation::default();
+        let enemies = [Vec3::new(-250.0, 0.0, 0.0), Vec3::new(50.0, 0.0, 0.0)];
+        assert_eq!(select_spawn_point(SpawnStrategy::FarthestFromEnemies, &SPAWN_POINTS, &enemies, &mut rotation), SPAWN_POINTS[2]);
+        assert_eq!(rotation.0, 0);
+    }
+
+    #[test]
+    fn farthest_strategy_rotates_without_enemies() {
+        let mut rotation = SpawnRotation::default();
+        assert_eq!(select_spawn_point(SpawnStrategy::FarthestFromEnemies, &SPAWN_POINTS, &[], &mut rotation), SPAWN_POINTS[0]);
+        assert_eq!(select_spawn_point(SpawnStrategy::FarthestFromEnemies, &SPAWN_POINTS, &[], &mut rotation), SPAWN_POINTS[1]);
+    }
+
+    #[test]
+    fn round_robin_wraps_around() {
+        let mut rotation = SpawnRotation(2);
+        let enemies = [SPAWN_POINTS[0]];
+        assert_eq!(select_spawn_point(SpawnStrategy::RoundRobin, &SPAWN_POINTS, &enemies, &mut rotation), SPAWN_POINTS[2]);
+        assert_eq!(select_spawn_point(SpawnStrategy::RoundRobin, &SPAWN_POINTS, &enemies, &mut rotation), SPAWN_POINTS[0]);
+        assert_eq!(select_spawn_point(SpawnStrategy::RoundRobin, &SPAWN_POINTS, &enemies, &mut rotation), SPAWN_POINTS[1]);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::plugin::{ServerLobby, ServerTick};
+use bevy::log::error;
+use bevy::prelude::{Changed, DetectChanges, Local, Query, Res, ResMut, Time};
+use bevy_renet2::prelude::RenetServer;
+use game_core::game_mode::component::{ScoreboardEntry, Team};
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::player::component::{PlayerName, PlayerNetwork};
+use std::cmp::Reverse;
+
+/// Minimum time between two scoreboards; damage changes on every hit.
+const SCOREBOARD_INTERVAL_SECS: f32 = 0.5;
+
+/// Sends the scoreboard after a join, a departure, a team change or new statistics.
+#[allow(clippy::too_many_arguments)]
+pub fn send_scoreboard(
+    mut server: ResMut<RenetServer>,
+    tick: Res<ServerTick>,
+    time: Res<Time>,
+    lobby: Res<ServerLobby>,
+    mut since_last_sent: Local<f32>,
+    mut pending: Local<bool>,
+    player_query: Query<(&PlayerNetwork, &PlayerName, Option<&Team>)>,
+    team_changes: Query<(), Changed<Team>>,
+) {
+    *since_last_sent += time.delta_secs();
+    *pending |= lobby.is_changed() || !team_changes.is_empty();
+    if !*pending || *since_last_sent < SCOREBOARD_INTERVAL_SECS {
+        return;
+    }
+
+    let mut players: Vec<ScoreboardEntry> = player_query.iter()
+        .map(|(player_network, player_name, team)| ScoreboardEntry {
+            id: player_network.id,
+            name: player_name.0.clone(),
+            team: team.copied(),
+            stats: lobby.stats.get(&player_network.id).copied().unwrap_or_default(),
+        })
+        .collect();
+    players.sort_by_key(|entry| (Reverse(entry.stats.kills), entry.stats.deaths, entry.id));
+
+    let message = match bincode::serialize(&TickedServerMessage {
+        tick: tick.0,
+        message: ServerMessages::Scoreboard { players },
+    }) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Erreur de sérialisation Scoreboard: {:?}", e);
+            return;
+        }
+    };
+    server.broadcast_message(ServerChannel::ServerMessages, message);
+
+    *since_last_sent = 0.0;
+    *pending = false;
+}
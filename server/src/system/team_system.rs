@@ -0,0 +1,103 @@
+use crate::plugin::ServerTick;
+use bevy::log::error;
+use bevy::prelude::{Commands, Entity, EventReader, Has, Query, Res, ResMut};
+use bevy_rapier2d::dynamics::RigidBodyDisabled;
+use bevy_rapier2d::geometry::ColliderDisabled;
+use bevy_renet2::prelude::{ClientId, RenetServer, ServerEvent};
+use game_core::game_mode::component::Team;
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::player::component::{Dead, LastAttacker, PlayerNetwork};
+
+pub fn team_of(team_query: &Query<(&PlayerNetwork, &Team)>, client_id: ClientId) -> Option<Team> {
+    team_query.iter()
+        .find(|(player_network, _)| player_network.id == client_id)
+        .map(|(_, team)| *team)
+}
+
+/// Team with fewer players, Red on a tie.
+pub fn smaller_team(teams: impl Iterator<Item = Team>) -> Team {
+    let blue_minus_red: i32 = teams.map(|team| if team == Team::Blue { 1 } else { -1 }).sum();
+    if blue_minus_red < 0 { Team::Blue } else { Team::Red }
+}
+
+/// Puts the player in `team` and sends it back to a spawn point, so it never fights next to its former teammates.
+pub fn change_team(
+    commands: &mut Commands,
+    entity: Entity,
+    client_id: ClientId,
+    team: Team,
+    tick: u64,
+    server: &mut RenetServer,
+) {
+    commands.entity(entity)
+        .insert((team, Dead::new(0.0), RigidBodyDisabled, ColliderDisabled))
+        .remove::<LastAttacker>();
+    send_team_changed(tick, client_id, team, None, server);
+}
+
+/// Sends to `to`, or to everyone when `None`.
+pub fn send_team_changed(
+    tick: u64,
+    client_id: ClientId,
+    team: Team,
+    to: Option<ClientId>,
+    server: &mut RenetServer,
+) {
+    let message = match bincode::serialize(&TickedServerMessage {
+        tick,
+        message: ServerMessages::TeamChanged { id: client_id, team },
+    }) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Erreur de sérialisation TeamChanged: {:?}", e);
+            return;
+        }
+    };
+
+    match to {
+        Some(to) => server.send_message(to, ServerChannel::ServerMessages, message),
+        None => server.broadcast_message(ServerChannel::ServerMessages, message),
+    }
+}
+
+/// Moves a player, dead ones first, when departures left a team two players short.
+pub fn balance_teams(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    mut server_events: EventReader<ServerEvent>,
+    tick: Res<ServerTick>,
+    player_query: Query<(Entity, &PlayerNetwork, &Team, Has<Dead>)>,
+) {
+    let client_left = server_events.read().any(|event| matches!(event, ServerEvent::ClientDisconnected { .. }));
+    if !client_left {
+        return;
+    }
+
+    let red = player_query.iter().filter(|(_, _, team, _)| **team == Team::Red).count();
+    let blue = player_query.iter().filter(|(_, _, team, _)| **team == Team::Blue).count();
+    let larger_team = match red.abs_diff(blue) {
+        0 | 1 => return,
+        _ if red > blue => Team::Red,
+        _ => Team::Blue,
+    };
+
+    let moved = player_query.iter()
+        .filter(|(_, _, team, _)| **team == larger_team)
+        .max_by_key(|(.., dead)| *dead);
+    if let Some((entity, player_network, ..)) = moved {
+        change_team(&mut commands, entity, player_network.id, larger_team.other(), tick.0, &mut server);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joining_players_fill_the_smaller_team() {
+        assert_eq!(smaller_team([].into_iter()), Team::Red);
+        assert_eq!(smaller_team([Team::Red].into_iter()), Team::Blue);
+        assert_eq!(smaller_team([Team::Red, Team::Blue].into_iter()), Team::Red);
+        assert_eq!(smaller_team([Team::Blue, Team::Blue, Team::Red].into_iter()), Team::Red);
+    }
+}
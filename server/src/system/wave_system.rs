@@ -0,0 +1,157 @@
+use crate::plugin::ServerTick;
+use bevy::log::error;
+use bevy::prelude::{Commands, Component, Entity, EventReader, Has, Query, Res, ResMut, Resource, Time, Timer, TimerMode, Transform, With};
+use bevy_renet2::prelude::{RenetServer, ServerEvent};
+use game_core::monster::command::spawn_monster_entity;
+use game_core::monster::component::{monster_physics, MonsterSpawner, MonsterType};
+use game_core::monster::wave::{WaveDefinitions, WaveProgress};
+use game_core::network::network::{ServerChannel, ServerMessages, TickedServerMessage};
+use game_core::player::component::{Dead, PlayerNetwork};
+
+/// Time the final wave stays on screen before the game restarts.
+const GAME_OVER_SECS: f32 = 8.0;
+
+/// Monster spawned by the survival mode, as opposed to a level spawner.
+#[derive(Debug, Component)]
+pub struct WaveMonster;
+
+#[derive(Debug)]
+pub enum WavePhase {
+    Intermission(Timer),
+    InProgress,
+    GameOver(Timer),
+}
+
+#[derive(Debug, Resource)]
+pub struct WaveState {
+    /// Current wave, or the one just cleared during an intermission; 0 before the first.
+    pub wave: u32,
+    pub phase: WavePhase,
+    /// Monsters of the current wave not spawned yet, the next one last.
+    pending: Vec<MonsterType>,
+    spawn_timer: Timer,
+    spawned: usize,
+    last_progress: Option<WaveProgress>,
+}
+
+impl WaveState {
+    pub fn new(definitions: &WaveDefinitions) -> Self {
+        Self {
+            wave: 0,
+            phase: WavePhase::Intermission(Timer::from_seconds(definitions.intermission_secs, TimerMode::Once)),
+            pending: Vec::new(),
+            spawn_timer: Timer::default(),
+            spawned: 0,
+            last_progress: None,
+        }
+    }
+
+    /// Dead players wait for the intermission to come back.
+    pub fn allows_respawn(&self) -> bool {
+        matches!(self.phase, WavePhase::Intermission(_))
+    }
+
+    fn progress(&self, alive_monsters: usize) -> WaveProgress {
+        match &self.phase {
+            WavePhase::Intermission(timer) => WaveProgress::Intermission { next_wave_in_secs: timer.remaining_secs() },
+            WavePhase::InProgress => WaveProgress::InProgress { remaining_monsters: (self.pending.len() + alive_monsters) as u32 },
+            WavePhase::GameOver(timer) => WaveProgress::GameOver { restart_in_secs: timer.remaining_secs() },
+        }
+    }
+
+    fn restart(&mut self, definitions: &WaveDefinitions) {
+        *self = Self { last_progress: self.last_progress, ..Self::new(definitions) };
+    }
+}
+
+/// Runs the survival mode: intermission, wave spawning, clear check and game over when every player is dead.
+#[allow(clippy::too_many_arguments)]
+pub fn run_waves(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    mut server_events: EventReader<ServerEvent>,
+    tick: Res<ServerTick>,
+    time: Res<Time>,
+    definitions: Res<WaveDefinitions>,
+    mut wave_state: ResMut<WaveState>,
+    spawner_query: Query<&Transform, With<MonsterSpawner>>,
+    monster_query: Query<Entity, With<WaveMonster>>,
+    player_query: Query<Has<Dead>, With<PlayerNetwork>>,
+) {
+    let client_joined = server_events.read().any(|event| matches!(event, ServerEvent::ClientConnected { .. }));
+    let mut alive_monsters = monster_query.iter().count();
+
+    if player_query.is_empty() {
+        if wave_state.wave > 0 {
+            despawn_wave_monsters(&mut commands, &monster_query);
+            wave_state.restart(&definitions);
+        }
+        return;
+    }
+
+    let wave_state = &mut *wave_state;
+    match &mut wave_state.phase {
+        WavePhase::Intermission(timer) => {
+            if timer.tick(time.delta()).finished() {
+                wave_state.wave += 1;
+                wave_state.pending = definitions.monsters(wave_state.wave);
+                wave_state.pending.reverse();
+                wave_state.spawn_timer = Timer::from_seconds(definitions.spawn_interval_secs(wave_state.wave), TimerMode::Repeating);
+                wave_state.phase = WavePhase::InProgress;
+            }
+        }
+        WavePhase::InProgress => {
+            if player_query.iter().all(|dead| dead) {
+                despawn_wave_monsters(&mut commands, &monster_query);
+                alive_monsters = 0;
+                wave_state.pending.clear();
+                wave_state.phase = WavePhase::GameOver(Timer::from_seconds(GAME_OVER_SECS, TimerMode::Once));
+            } else if !wave_state.pending.is_empty() {
+                let spawn_points: Vec<_> = spawner_query.iter().map(|transform| transform.translation).collect();
+                if wave_state.spawn_timer.tick(time.delta()).just_finished() && !spawn_points.is_empty()
+                    && let Some(monster_type) = wave_state.pending.pop()
+                {
+                    let position = spawn_points[wave_state.spawned % spawn_points.len()];
+                    let monster = spawn_monster_entity(&mut commands, monster_type, position);
+                    commands.entity(monster).insert((monster_physics(&monster_type), WaveMonster));
+                    wave_state.spawned += 1;
+                    alive_monsters += 1;
+                }
+            } else if alive_monsters == 0 {
+                wave_state.phase = WavePhase::Intermission(Timer::from_seconds(definitions.intermission_secs, TimerMode::Once));
+            }
+        }
+        WavePhase::GameOver(timer) => {
+            if timer.tick(time.delta()).finished() {
+                wave_state.restart(&definitions);
+            }
+        }
+    }
+
+    let progress = wave_state.progress(alive_monsters);
+    let phase_changed = wave_state.last_progress
+        .is_none_or(|last| std::mem::discriminant(&last) != std::mem::discriminant(&progress));
+    let remaining_changed = matches!(progress, WaveProgress::InProgress { .. }) && wave_state.last_progress != Some(progress);
+    wave_state.last_progress = Some(progress);
+    if !(client_joined || phase_changed || remaining_changed) {
+        return;
+    }
+
+    let message = match bincode::serialize(&TickedServerMessage {
+        tick: tick.0,
+        message: ServerMessages::WaveStatus { wave: wave_state.wave, progress },
+    }) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!("Erreur de sérialisation WaveStatus: {:?}", e);
+            return;
+        }
+    };
+    server.broadcast_message(ServerChannel::ServerMessages, message);
+}
+
+fn despawn_wave_monsters(commands: &mut Commands, monster_query: &Query<Entity, With<WaveMonster>>) {
+    for monster in monster_query.iter() {
+        commands.entity(monster).despawn();
+    }
+}